@@ -0,0 +1,80 @@
+//! Compare writing each element of a format with writing each record at once.
+//!
+//! Run with `cargo bench`. The number of write syscalls is read from
+//! `/proc/self/io`, so it is only shown on Linux.
+use fmtlog::{Config, Format, Logger};
+use log::{Level, Log, Record};
+use std::time::{Duration, Instant};
+use std::{env, fs, process};
+
+const RECORDS: usize = 100_000;
+const FORMAT: &str = "%l [%N] (%S) %M\n";
+
+fn main() {
+    let path = env::temp_dir().join(format!("fmtlog-bench-{}.log", process::id()));
+    let record = Record::builder()
+        .args(format_args!("Something has failed."))
+        .level(Level::Error)
+        .target("app::db")
+        .file(Some("src/db.rs"))
+        .line(Some(42))
+        .build();
+
+    // Write each element to the file directly.
+    let format = Format::new(FORMAT).unwrap();
+    let mut file = fs::File::create(&path).unwrap();
+    let (elapsed, syscalls) = measure(|| {
+        for _ in 0..RECORDS {
+            format.write_to(&mut file, &record, false).unwrap();
+        }
+    });
+    report("Format::write_to (unbuffered file)", elapsed, syscalls);
+    drop(file);
+    fs::remove_file(&path).unwrap();
+
+    // Render each record into the buffer and write it at once.
+    let logger = Logger::new(Config::new().format(FORMAT).output(path.clone()));
+    let (elapsed, syscalls) = measure(|| {
+        for _ in 0..RECORDS {
+            logger.log(&record);
+        }
+    });
+    report("Logger::log (Output::File)", elapsed, syscalls);
+    drop(logger);
+    fs::remove_file(&path).unwrap();
+}
+
+fn measure<F: FnOnce()>(f: F) -> (Duration, Option<u64>) {
+    let before = write_syscalls();
+    let start = Instant::now();
+    f();
+    let elapsed = start.elapsed();
+    let after = write_syscalls();
+
+    (elapsed, before.zip(after).map(|(b, a)| a - b))
+}
+
+fn report(name: &str, elapsed: Duration, syscalls: Option<u64>) {
+    let syscalls = match syscalls {
+        Some(n) => format!("{:.1}", n as f64 / RECORDS as f64),
+        None => String::from("n/a"),
+    };
+
+    println!(
+        "{:<40} {:>8.0} ns/record {:>6} write syscalls/record",
+        name,
+        elapsed.as_nanos() as f64 / RECORDS as f64,
+        syscalls
+    );
+}
+
+// The number of write syscalls of this process. (Linux only)
+fn write_syscalls() -> Option<u64> {
+    fs::read_to_string("/proc/self/io")
+        .ok()?
+        .lines()
+        .find_map(|l| l.strip_prefix("syscw:"))?
+        .trim()
+        .parse()
+        .ok()
+}
@@ -2,22 +2,17 @@ use super::Output;
 use std::fmt;
 
 /// Colorize the output
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Colorize {
     /// Never colorize.
     Off,
     /// Detect by the output target.
+    #[default]
     Auto,
     /// Always colorize.
     On,
 }
 
-impl Default for Colorize {
-    fn default() -> Self {
-        Colorize::Auto
-    }
-}
-
 impl fmt::Display for Colorize {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         write!(
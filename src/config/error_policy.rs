@@ -0,0 +1,68 @@
+use super::Output;
+use std::{fmt, io, sync::Arc};
+
+/// How to handle errors while writing logs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Ignore all errors.
+    Ignore,
+    /// Report the first error to the standard error.
+    #[default]
+    Report,
+    /// Write the log to another output instead.
+    Fallback(Output),
+    /// Call the function with the failed output and the error.
+    Callback(ErrorCallback),
+}
+
+impl ErrorPolicy {
+    /// Create `ErrorPolicy::Callback` from a function.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, ErrorPolicy};
+    ///
+    /// let config = Config::new().on_error(ErrorPolicy::callback(|output, error| {
+    ///     // Report to the monitoring system, etc.
+    ///     let _ = (output, error);
+    /// }));
+    /// ```
+    pub fn callback<F>(f: F) -> Self
+    where
+        F: Fn(&Output, &io::Error) + Send + Sync + 'static,
+    {
+        Self::Callback(ErrorCallback(Arc::new(f)))
+    }
+}
+
+impl From<Output> for ErrorPolicy {
+    fn from(o: Output) -> Self {
+        Self::Fallback(o)
+    }
+}
+
+/// A function called on errors. (See [`ErrorPolicy::callback`](enum.ErrorPolicy.html#method.callback).)
+#[derive(Clone)]
+#[allow(clippy::type_complexity)]
+pub struct ErrorCallback(Arc<dyn Fn(&Output, &io::Error) + Send + Sync>);
+
+impl ErrorCallback {
+    pub(crate) fn call(&self, output: &Output, error: &io::Error) {
+        (self.0)(output, error)
+    }
+}
+
+impl fmt::Debug for ErrorCallback {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "ErrorCallback")
+    }
+}
+
+impl PartialEq for ErrorCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ErrorCallback {}
@@ -0,0 +1,39 @@
+use std::fmt;
+
+/// The key used to filter logs by modules.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FilterKey {
+    /// Filter by the module path. (Falls back to the target when it is missing.)
+    #[default]
+    ModulePath,
+    /// Filter by the target.
+    Target,
+    /// Filter by both the target and the module path.
+    Both,
+}
+
+impl fmt::Display for FilterKey {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{}",
+            match self {
+                Self::ModulePath => "module_path",
+                Self::Target => "target",
+                Self::Both => "both",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for FilterKey {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "module_path" | "module" | "ModulePath" | "MODULE_PATH" => Ok(Self::ModulePath),
+            "target" | "Target" | "TARGET" => Ok(Self::Target),
+            "both" | "Both" | "BOTH" => Ok(Self::Both),
+            e => Err(format!("Invalid string:\"{}\"", e)),
+        }
+    }
+}
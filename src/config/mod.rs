@@ -1,10 +1,18 @@
 //! Configuration module.
 #[cfg(feature = "colored")]
 mod colorize;
+mod error_policy;
+mod filter_key;
 mod output;
+mod rotation;
 
+pub use error_policy::{ErrorCallback, ErrorPolicy};
+pub use filter_key::FilterKey;
 pub use log::LevelFilter;
-pub use output::Output;
+pub use output::{Framing, Output};
+#[cfg(feature = "chrono")]
+pub(crate) use rotation::Period;
+pub use rotation::RotatingFile;
 
 #[cfg(feature = "colored")]
 pub use colorize::Colorize;
@@ -16,8 +24,13 @@ pub struct Config {
     pub(crate) colorize: Colorize,
     pub(crate) format: String,
     pub(crate) level: LevelFilter,
+    pub(crate) filter_key: FilterKey,
     pub(crate) modules: Vec<String>,
+    pub(crate) excludes: Vec<String>,
+    pub(crate) directives: Vec<(String, LevelFilter)>,
+    pub(crate) message: Option<String>,
     pub(crate) output: Vec<Output>,
+    pub(crate) on_error: ErrorPolicy,
 }
 
 impl Default for Config {
@@ -32,8 +45,13 @@ impl Default for Config {
             format: String::from(crate::formats::SIMPLE1),
 
             level: LevelFilter::Info,
+            filter_key: FilterKey::default(),
             modules: Vec::new(),
+            excludes: Vec::new(),
+            directives: Vec::new(),
+            message: None,
             output: vec![Output::default()],
+            on_error: ErrorPolicy::default(),
         }
     }
 }
@@ -44,6 +62,119 @@ impl Config {
         Self::default()
     }
 
+    /// Create a new instance from a filter string like `RUST_LOG` of `env_logger`.
+    ///
+    /// The string is a comma-separated list of directives:
+    ///
+    /// - `<level>` sets the default log level.
+    /// - `<module>` enables all logs of the module.
+    /// - `<module>=<level>` sets the log level of the module.
+    ///   (`=<level>` without the module sets the default log level.)
+    ///
+    /// A trailing `/<regex>` filters logs by their messages.
+    /// (Without feature `regex`, the pattern is matched as a substring.)
+    ///
+    /// When only modules are specified, other modules are turned off.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, LevelFilter};
+    ///
+    /// assert_eq!(
+    ///     Config::parse_filter("hyper=warn,my_app::db=trace,info").unwrap(),
+    ///     Config::new()
+    ///         .level(LevelFilter::Info)
+    ///         .module_level("hyper", LevelFilter::Warn)
+    ///         .module_level("my_app::db", LevelFilter::Trace)
+    /// );
+    /// assert_eq!(
+    ///     Config::parse_filter("=debug").unwrap(),
+    ///     Config::new().level(LevelFilter::Debug)
+    /// );
+    /// assert!(Config::parse_filter("my_app=verbose").is_err());
+    /// ```
+    pub fn parse_filter<T: AsRef<str>>(filter: T) -> Result<Self, String> {
+        let mut config = Self::new();
+
+        let mut parts = filter.as_ref().splitn(2, '/');
+        let directives = parts.next().unwrap_or_default();
+        let message = parts.next();
+
+        let mut level = None;
+        for directive in directives
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+        {
+            let mut parts = directive.splitn(2, '=');
+            let name = parts.next().unwrap_or_default().trim();
+
+            let l = match parts.next().map(str::trim) {
+                // "<level>" or "<module>"
+                None => match name.parse() {
+                    Ok(l) => {
+                        level = Some(l);
+                        continue;
+                    }
+                    Err(_) => LevelFilter::Trace,
+                },
+                // "<module>="
+                Some("") => LevelFilter::Trace,
+                // "<module>=<level>"
+                Some(l) => l
+                    .parse()
+                    .map_err(|_| format!("Invalid log level: \"{}\"", l))?,
+            };
+
+            // "=<level>" matches all modules like "<level>".
+            if name.is_empty() {
+                level = Some(l);
+            } else {
+                config = config.module_level(name, l);
+            }
+        }
+
+        config.level = match level {
+            Some(l) => l,
+            None if !config.directives.is_empty() => LevelFilter::Off,
+            None => config.level,
+        };
+
+        if let Some(m) = message {
+            #[cfg(feature = "regex")]
+            regex::Regex::new(m).map_err(|e| format!("Invalid regex: {}", e))?;
+            config.message = Some(m.to_string());
+        }
+
+        Ok(config)
+    }
+
+    /// Create a new instance from an environment variable.
+    ///
+    /// The value is parsed by [`Config::parse_filter`](#method.parse_filter).
+    /// When the variable is not set, the default settings are used.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::Config;
+    ///
+    /// std::env::set_var("MYAPP_LOG", "my_app=debug,warn");
+    ///
+    /// assert_eq!(
+    ///     Config::from_env("MYAPP_LOG").unwrap(),
+    ///     Config::parse_filter("my_app=debug,warn").unwrap()
+    /// );
+    /// ```
+    pub fn from_env<T: AsRef<std::ffi::OsStr>>(name: T) -> Result<Self, String> {
+        match std::env::var(name) {
+            Ok(filter) => Self::parse_filter(filter),
+            Err(std::env::VarError::NotPresent) => Ok(Self::new()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
     /// [**colored**] Colorize the log.
     ///
     /// # Example
@@ -87,6 +218,45 @@ impl Config {
         self
     }
 
+    /// Set the key used to filter logs by modules.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, FilterKey, Logger};
+    /// use log::{Level, Log, Record};
+    ///
+    /// let path = std::env::temp_dir().join(format!("fmtlog-doc-key-{}.log", std::process::id()));
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// // Only logs with the target "audit" are written.
+    /// let logger = Logger::new(
+    ///     Config::new()
+    ///         .format("%M\n")
+    ///         .output(path.clone())
+    ///         .filter_key(FilterKey::Target)
+    ///         .module("audit"),
+    /// );
+    ///
+    /// for &(target, message) in &[("audit", "Logged in."), ("my_app::x", "Dropped.")] {
+    ///     logger.log(
+    ///         &Record::builder()
+    ///             .args(format_args!("{}", message))
+    ///             .level(Level::Info)
+    ///             .target(target)
+    ///             .module_path(Some("my_app::x"))
+    ///             .build(),
+    ///     );
+    /// }
+    ///
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "Logged in.\n");
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn filter_key<T: Into<FilterKey>>(mut self, key: T) -> Self {
+        self.filter_key = key.into();
+        self
+    }
+
     /// Set modules that enable the logger.
     ///
     /// # Example
@@ -118,6 +288,143 @@ impl Config {
         self
     }
 
+    /// Add a module that disables the logger.
+    ///
+    /// The children of the module are also disabled.
+    /// Excluded modules take precedence over included modules and module levels.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, LevelFilter, Logger};
+    /// use log::{Level, Log, Record};
+    ///
+    /// let path = std::env::temp_dir().join(format!("fmtlog-doc-exclude-{}.log", std::process::id()));
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let logger = Logger::new(
+    ///     Config::new()
+    ///         .format("%N\n")
+    ///         .output(path.clone())
+    ///         .module_level("h2", LevelFilter::Trace)
+    ///         .exclude_module("h2::codec")
+    ///         .exclude_module("tokio_util::codec"),
+    /// );
+    ///
+    /// let records = [
+    ///     ("h2::codec::framed", Level::Trace),
+    ///     ("h2::proto", Level::Trace),
+    ///     ("tokio_util::codec", Level::Info),
+    ///     ("tokio_util::io", Level::Info),
+    /// ];
+    ///
+    /// for &(module, level) in &records {
+    ///     logger.log(
+    ///         &Record::builder()
+    ///             .args(format_args!("Hello!"))
+    ///             .level(level)
+    ///             .target(module)
+    ///             .module_path(Some(module))
+    ///             .build(),
+    ///     );
+    /// }
+    ///
+    /// assert_eq!(
+    ///     std::fs::read_to_string(&path).unwrap(),
+    ///     "h2::proto\ntokio_util::io\n"
+    /// );
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn exclude_module<T: Into<String>>(mut self, module: T) -> Self {
+        self.excludes.push(module.into());
+        self
+    }
+
+    /// Set modules that disable the logger.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::Config;
+    ///
+    /// assert_eq!(
+    ///     Config::new().exclude_modules(vec!["h2", "tokio_util::codec"]),
+    ///     Config::new().exclude_module("h2").exclude_module("tokio_util::codec")
+    /// );
+    /// ```
+    pub fn exclude_modules<T: IntoIterator>(mut self, modules: T) -> Self
+    where
+        T::Item: Into<String>,
+    {
+        self.excludes = modules.into_iter().map(|x| x.into()).collect();
+        self
+    }
+
+    /// Set the log level of a module.
+    ///
+    /// The level applies to the module and its children.
+    /// When several modules match, the longest one is used.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, LevelFilter, Logger};
+    /// use log::{Level, Log, Record};
+    ///
+    /// let path = std::env::temp_dir().join(format!("fmtlog-doc-level-{}.log", std::process::id()));
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// // hyper=warn,my_app=warn,my_app::db=trace,info
+    /// let logger = Logger::new(
+    ///     Config::new()
+    ///         .format("%l %N\n")
+    ///         .output(path.clone())
+    ///         .level(LevelFilter::Info)
+    ///         .module_level("hyper", LevelFilter::Warn)
+    ///         .module_level("my_app", LevelFilter::Warn)
+    ///         .module_level("my_app::db", LevelFilter::Trace),
+    /// );
+    ///
+    /// let records = [
+    ///     ("hyper::client", Level::Info),
+    ///     ("hyper::client", Level::Warn),
+    ///     ("my_app::db::pool", Level::Trace),
+    ///     ("my_app::api", Level::Info),
+    ///     ("tokio", Level::Info),
+    /// ];
+    ///
+    /// for &(module, level) in &records {
+    ///     logger.log(
+    ///         &Record::builder()
+    ///             .args(format_args!("Hello!"))
+    ///             .level(level)
+    ///             .target(module)
+    ///             .module_path(Some(module))
+    ///             .build(),
+    ///     );
+    /// }
+    ///
+    /// assert_eq!(
+    ///     std::fs::read_to_string(&path).unwrap(),
+    ///     "warn hyper::client\ntrace my_app::db::pool\ninfo tokio\n"
+    /// );
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn module_level<T: Into<String>, L: Into<LevelFilter>>(
+        mut self,
+        module: T,
+        level: L,
+    ) -> Self {
+        let module = module.into();
+        let level = level.into();
+
+        match self.directives.iter_mut().find(|d| d.0 == module) {
+            Some(d) => d.1 = level,
+            None => self.directives.push((module, level)),
+        }
+        self
+    }
+
     /// Set the output stream.
     ///
     /// # Example
@@ -164,4 +471,21 @@ impl Config {
         self.output = outputs.into_iter().map(|x| x.into()).collect();
         self
     }
+
+    /// Set how to handle errors while writing logs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, ErrorPolicy, Output};
+    ///
+    /// assert_eq!(
+    ///     Config::new().on_error(Output::Stderr),
+    ///     Config::new().on_error(ErrorPolicy::Fallback(Output::Stderr))
+    /// );
+    /// ```
+    pub fn on_error<T: Into<ErrorPolicy>>(mut self, policy: T) -> Self {
+        self.on_error = policy.into();
+        self
+    }
 }
@@ -1,28 +1,323 @@
-use crate::Stream;
+use crate::network::{Network, Protocol};
+use crate::rotate::Rotator;
+use crate::{RotatingFile, Stream};
 use std::{fmt, fs, io, path};
 
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+
 /// The Output type
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub enum Output {
     /// Standard Output
     Stdout,
     /// Standard Error
+    #[default]
     Stderr,
     /// File Stream
     File(path::PathBuf),
+    /// Rotating File Stream (See [`RotatingFile`](struct.RotatingFile.html).)
+    Rotating(RotatingFile),
+    /// TCP Stream (`<host>:<port>`)
+    ///
+    /// Logs are sent by a background thread, so logging never waits for the network.
+    /// The connection is reopened with backoff when the peer goes away,
+    /// and logs are buffered while disconnected. (Up to 1024 logs, the oldest
+    /// logs are dropped and reported to the error policy.)
+    ///
+    /// A log being sent when the connection breaks is sent again on the new connection.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Framing, Output};
+    ///
+    /// let config = Config::new().output(Output::Tcp("127.0.0.1:5170".into(), Framing::Newline));
+    /// assert_eq!(config, Config::new().output("tcp://127.0.0.1:5170".parse::<Output>().unwrap()));
+    /// ```
+    ///
+    /// Logs are buffered until the collector starts:
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, ErrorPolicy, Framing, Logger, Output};
+    /// use log::{Log, Record};
+    /// use std::io::Read;
+    /// use std::net::TcpListener;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// // Find a free port.
+    /// let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    ///
+    /// let errors = Arc::new(Mutex::new(Vec::new()));
+    /// let errors2 = errors.clone();
+    /// let logger = Logger::new(
+    ///     Config::new()
+    ///         .format("%M")
+    ///         .output(Output::Tcp(addr.to_string(), Framing::OctetCounting))
+    ///         .on_error(ErrorPolicy::callback(move |_, e| {
+    ///             errors2.lock().unwrap().push(e.to_string())
+    ///         })),
+    /// );
+    ///
+    /// // The buffer is full, so the 2 oldest logs are dropped.
+    /// for i in 0..1026 {
+    ///     logger.log(&Record::builder().args(format_args!("#{}", i)).build());
+    /// }
+    /// assert_eq!(errors.lock().unwrap().len(), 2);
+    ///
+    /// let listener = TcpListener::bind(addr).unwrap();
+    /// let (mut conn, _) = listener.accept().unwrap();
+    /// logger.flush();
+    /// drop(logger);
+    ///
+    /// let mut buf = String::new();
+    /// conn.read_to_string(&mut buf).unwrap();
+    ///
+    /// // "<length> <log>"
+    /// let mut logs = Vec::new();
+    /// let mut rest = buf.as_str();
+    /// while let Some((len, log)) = rest.split_once(' ') {
+    ///     let len: usize = len.parse().unwrap();
+    ///     logs.push(&log[..len]);
+    ///     rest = &log[len..];
+    /// }
+    /// assert_eq!(logs.len(), 1024);
+    /// assert_eq!(logs.last(), Some(&"#1025"));
+    /// ```
+    ///
+    /// The connection is reopened:
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Framing, Logger, Output};
+    /// use log::{Log, Record};
+    /// use std::io::{BufRead, BufReader};
+    /// use std::net::TcpListener;
+    /// use std::time::Duration;
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// let addr = listener.local_addr().unwrap().to_string();
+    /// let logger = Logger::new(
+    ///     Config::new()
+    ///         .format("%M")
+    ///         .output(Output::Tcp(addr, Framing::Newline)),
+    /// );
+    ///
+    /// let log = |message| logger.log(&Record::builder().args(format_args!("{}", message)).build());
+    ///
+    /// log("Hello!");
+    /// let (conn, _) = listener.accept().unwrap();
+    /// let mut lines = BufReader::new(conn).lines();
+    /// assert_eq!(lines.next().unwrap().unwrap(), "Hello!");
+    ///
+    /// // The collector restarts.
+    /// drop(lines);
+    /// listener.set_nonblocking(true).unwrap();
+    /// let conn = loop {
+    ///     // Logs written to the closed connection may be lost.
+    ///     log("Retry");
+    ///     match listener.accept() {
+    ///         Ok((conn, _)) => break conn,
+    ///         Err(_) => std::thread::sleep(Duration::from_millis(10)),
+    ///     }
+    /// };
+    /// conn.set_nonblocking(false).unwrap();
+    ///
+    /// log("Reconnected!");
+    /// logger.flush();
+    /// drop(logger);
+    ///
+    /// let lines: Vec<_> = BufReader::new(conn).lines().map(Result::unwrap).collect();
+    /// assert_eq!(lines.last().unwrap(), "Reconnected!");
+    /// assert!(lines.iter().all(|l| l == "Retry" || l == "Reconnected!"));
+    /// ```
+    Tcp(String, Framing),
+    /// UDP Socket (`<host>:<port>`, Each log is sent as one datagram.)
+    ///
+    /// Logs are sent by a background thread like [`Output::Tcp`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Logger, Output};
+    /// use log::{Log, Record};
+    /// use std::net::UdpSocket;
+    /// use std::time::Duration;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    /// socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    /// let addr = socket.local_addr().unwrap().to_string();
+    ///
+    /// let logger = Logger::new(Config::new().format("%M\n").output(Output::Udp(addr)));
+    /// logger.log(&Record::builder().args(format_args!("Hello!")).build());
+    /// logger.log(&Record::builder().args(format_args!("Line 1\nLine 2")).build());
+    /// logger.flush();
+    ///
+    /// let mut buf = [0; 1024];
+    /// let len = socket.recv(&mut buf).unwrap();
+    /// assert_eq!(&buf[..len], b"Hello!\n");
+    /// let len = socket.recv(&mut buf).unwrap();
+    /// assert_eq!(&buf[..len], b"Line 1\nLine 2\n");
+    /// ```
+    Udp(String),
+    /// [**unix**] The local syslog daemon. (`/dev/log`)
+    ///
+    /// Use with a syslog format like [`formats::RFC3164`](formats/constant.RFC3164.html).
+    #[cfg(unix)]
+    Syslog,
+    /// [**unix**] Unix Datagram Socket (Each log is sent as one datagram.)
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Logger, Output};
+    /// use log::{Level, Log, Record};
+    /// use std::os::unix::net::UnixDatagram;
+    ///
+    /// let path = std::env::temp_dir().join(format!("fmtlog-doc-{}.sock", std::process::id()));
+    /// let socket = UnixDatagram::bind(&path).unwrap();
+    ///
+    /// let logger = Logger::new(
+    ///     Config::new()
+    ///         .format("%L: %M")
+    ///         .output(Output::UnixDatagram(path.clone())),
+    /// );
+    /// logger.log(
+    ///     &Record::builder()
+    ///         .args(format_args!("Hello!"))
+    ///         .level(Level::Info)
+    ///         .build(),
+    /// );
+    ///
+    /// let mut buf = [0; 64];
+    /// let len = socket.recv(&mut buf).unwrap();
+    /// assert_eq!(&buf[..len], b"INFO: Hello!");
+    ///
+    /// std::fs::remove_file(path).unwrap();
+    /// ```
+    #[cfg(unix)]
+    UnixDatagram(path::PathBuf),
+    /// [**unix**] systemd-journald (The native protocol)
+    ///
+    /// Logs are sent with fields `MESSAGE`, `PRIORITY`, `SYSLOG_IDENTIFIER`, `TARGET`,
+    /// `CODE_FILE`, `CODE_LINE`, `CODE_MODULE` and key-values. (Requires feature `kv`.)
+    /// The format is not used.
+    ///
+    /// Keys are converted to uppercase field names, and keys conflicting with the fields
+    /// above are prefixed with `FIELD_`. (e.g. `priority` is sent as `FIELD_PRIORITY`)
+    ///
+    /// Each log is sent as one datagram, so logs larger than the socket buffer
+    /// (`net.core.wmem_default` on Linux) fail with `EMSGSIZE` every time, and are
+    /// handled by [`ErrorPolicy`](enum.ErrorPolicy.html).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use fmtlog::{Config, Output};
+    ///
+    /// fmtlog::new(Config::new().output(Output::Journald)).set().unwrap();
+    /// ```
+    #[cfg(unix)]
+    Journald,
+    /// [**unix**] systemd-journald listening on another socket. (e.g. in a container)
+    ///
+    /// Logs are sent like [`Output::Journald`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Logger, Output};
+    /// use log::{Level, Log, Record};
+    /// use std::os::unix::net::UnixDatagram;
+    ///
+    /// let path = std::env::temp_dir().join(format!("fmtlog-journald-{}.sock", std::process::id()));
+    /// let socket = UnixDatagram::bind(&path).unwrap();
+    ///
+    /// let logger = Logger::new(Config::new().output(Output::JournaldSocket(path.clone())));
+    /// logger.log(
+    ///     &Record::builder()
+    ///         .args(format_args!("Hello,\nworld!"))
+    ///         .level(Level::Warn)
+    ///         .target("app")
+    ///         .line(Some(42))
+    ///         .build(),
+    /// );
+    ///
+    /// let mut buf = [0; 1024];
+    /// let len = socket.recv(&mut buf).unwrap();
+    ///
+    /// // Multi-line values are sent in the binary form: "<name>\n<length (u64 LE)><value>\n"
+    /// let message = b"MESSAGE\n\x0d\0\0\0\0\0\0\0Hello,\nworld!\n";
+    /// assert!(buf[..len].starts_with(message));
+    ///
+    /// let fields = std::str::from_utf8(&buf[message.len()..len]).unwrap();
+    /// let fields: Vec<_> = fields.lines().collect();
+    /// assert!(fields.contains(&"PRIORITY=4"));
+    /// assert!(fields.contains(&"TARGET=app"));
+    /// assert!(fields.contains(&"CODE_LINE=42"));
+    ///
+    /// std::fs::remove_file(path).unwrap();
+    /// ```
+    ///
+    /// Key-values are prefixed when they conflict, and truncated to 64 characters:
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "kv")]
+    /// # {
+    /// use fmtlog::{Config, Logger, Output};
+    /// use log::{Log, Record};
+    /// use std::os::unix::net::UnixDatagram;
+    ///
+    /// let path = std::env::temp_dir().join(format!("fmtlog-journald-kv-{}.sock", std::process::id()));
+    /// let socket = UnixDatagram::bind(&path).unwrap();
+    ///
+    /// let logger = Logger::new(Config::new().output(Output::JournaldSocket(path.clone())));
+    /// let long_key = "k".repeat(70);
+    /// logger.log(
+    ///     &Record::builder()
+    ///         .args(format_args!("Hello!"))
+    ///         .key_values(&[("priority", "high"), ("code_func", "main"), (&long_key, "long")])
+    ///         .build(),
+    /// );
+    ///
+    /// let mut buf = [0; 1024];
+    /// let len = socket.recv(&mut buf).unwrap();
+    /// let fields: Vec<_> = std::str::from_utf8(&buf[..len]).unwrap().lines().collect();
+    ///
+    /// assert!(fields.contains(&"MESSAGE=Hello!"));
+    /// assert!(fields.contains(&"FIELD_PRIORITY=high"));
+    /// assert!(fields.contains(&"FIELD_CODE_FUNC=main"));
+    /// assert!(fields.contains(&format!("{}=long", "K".repeat(64)).as_str()));
+    ///
+    /// std::fs::remove_file(path).unwrap();
+    /// # }
+    /// ```
+    #[cfg(unix)]
+    JournaldSocket(path::PathBuf),
 }
 
 impl fmt::Display for Output {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            fmt,
-            "{}",
-            match self {
-                Self::Stdout => "<stdout>",
-                Self::Stderr => "<stderr>",
-                Self::File(path) => path.to_str().unwrap_or("<???>"),
+        match self {
+            Self::Stdout => write!(fmt, "<stdout>"),
+            Self::Stderr => write!(fmt, "<stderr>"),
+            Self::File(path) | Self::Rotating(RotatingFile { path, .. }) => {
+                write!(fmt, "{}", path.to_str().unwrap_or("<???>"))
+            }
+            Self::Tcp(addr, _) => write!(fmt, "tcp://{}", addr),
+            Self::Udp(addr) => write!(fmt, "udp://{}", addr),
+            #[cfg(unix)]
+            Self::Syslog => write!(fmt, "<syslog>"),
+            #[cfg(unix)]
+            Self::UnixDatagram(path) => {
+                write!(fmt, "unixgram:{}", path.to_str().unwrap_or("<???>"))
+            }
+            #[cfg(unix)]
+            Self::Journald => write!(fmt, "<journald>"),
+            #[cfg(unix)]
+            Self::JournaldSocket(path) => {
+                write!(fmt, "journald:{}", path.to_str().unwrap_or("<???>"))
             }
-        )
+        }
     }
 }
 
@@ -32,14 +327,40 @@ impl std::str::FromStr for Output {
         Ok(match s {
             "stdout" | "Stdout" | "StdOut" | "STDOUT" | "<stdout>" => Self::Stdout,
             "stderr" | "Stderr" | "StdErr" | "STDERR" | "<stderr>" => Self::Stderr,
+            addr if addr.starts_with("tcp://") => {
+                Self::Tcp(addr["tcp://".len()..].into(), Framing::default())
+            }
+            addr if addr.starts_with("udp://") => Self::Udp(addr["udp://".len()..].into()),
+            #[cfg(unix)]
+            "syslog" | "Syslog" | "SYSLOG" | "<syslog>" => Self::Syslog,
+            #[cfg(unix)]
+            "journald" | "Journald" | "JOURNALD" | "<journald>" => Self::Journald,
+            #[cfg(unix)]
+            path if path.starts_with("journald:") => {
+                Self::JournaldSocket(path["journald:".len()..].into())
+            }
+            #[cfg(unix)]
+            path if path.starts_with("unixgram:") => {
+                Self::UnixDatagram(path["unixgram:".len()..].into())
+            }
             path => Self::File(path.into()),
         })
     }
 }
 
-impl Default for Output {
-    fn default() -> Self {
-        Self::Stderr
+/// The framing of TCP streams.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Framing {
+    /// Terminate each log with a newline.
+    #[default]
+    Newline,
+    /// Prefix each log with its length. (RFC 6587, e.g. `12 <14>1 ...`)
+    OctetCounting,
+}
+
+impl From<RotatingFile> for Output {
+    fn from(file: RotatingFile) -> Self {
+        Output::Rotating(file)
     }
 }
 
@@ -57,6 +378,13 @@ fn new_file(path: path::PathBuf) -> io::Result<fs::File> {
         .or_else(|_| fs::File::create(path))
 }
 
+#[cfg(unix)]
+fn new_datagram<P: AsRef<path::Path>>(path: P) -> io::Result<UnixDatagram> {
+    let socket = UnixDatagram::unbound()?;
+    socket.connect(path)?;
+    Ok(socket)
+}
+
 impl Output {
     /// Create `Stream` from `Output`.
     pub(crate) fn to_stream(&self) -> io::Result<Stream> {
@@ -64,6 +392,17 @@ impl Output {
             Self::Stdout => Stream::from(io::stdout()),
             Self::Stderr => Stream::from(io::stderr()),
             Self::File(path) => Stream::from(new_file(path)?),
+            Self::Rotating(file) => Stream::from(Rotator::new(file)?),
+            Self::Tcp(addr, framing) => Stream::from(Network::new(addr, Protocol::Tcp(framing))?),
+            Self::Udp(addr) => Stream::from(Network::new(addr, Protocol::Udp)?),
+            #[cfg(unix)]
+            Self::Syslog => Stream::from(new_datagram("/dev/log")?),
+            #[cfg(unix)]
+            Self::UnixDatagram(path) => Stream::from(new_datagram(path)?),
+            #[cfg(unix)]
+            Self::Journald => Stream::Journald(new_datagram(crate::journald::SOCKET)?),
+            #[cfg(unix)]
+            Self::JournaldSocket(path) => Stream::Journald(new_datagram(path)?),
         })
     }
 }
@@ -0,0 +1,400 @@
+use std::{path, time};
+
+/// The settings of a rotating file.
+///
+/// When the file would exceed the max size, it is renamed to `<path>.1`,
+/// the old `<path>.1` is renamed to `<path>.2`, and so on.
+///
+/// With a period (requires feature `chrono`), the file is also rotated at
+/// the start of each day or hour, and the path may contain the patterns of
+/// [`chrono::format::strftime`](https://docs.rs/chrono/latest/chrono/format/strftime/index.html)
+/// in the local time. When the path changes, a new file is opened instead of renaming.
+///
+/// Old files are compressed and removed by the retention policy in a background
+/// thread after each rotation. Rotated files are searched in the directory of the
+/// file, so the patterns can't be used in the directory names.
+///
+/// # Example
+///
+/// ```rust
+/// use fmtlog::{Config, RotatingFile};
+///
+/// // app.log, app.log.1, ..., app.log.5
+/// let config = Config::new().output(
+///     RotatingFile::new("app.log")
+///         .max_size(10 * 1024 * 1024)
+///         .keep(5),
+/// );
+///
+/// assert_ne!(Config::new().output("app.log"), config);
+/// ```
+///
+/// ```rust
+/// use fmtlog::{Config, RotatingFile};
+/// use std::time::Duration;
+///
+/// // Keep files for a week, up to 1GB.
+/// let config = Config::new().output(
+///     RotatingFile::new("app.log")
+///         .max_size(10 * 1024 * 1024)
+///         .max_total_size(1024 * 1024 * 1024)
+///         .max_age(Duration::from_secs(7 * 24 * 60 * 60)),
+/// );
+/// ```
+///
+/// ```rust
+/// # #[cfg(feature = "chrono")]
+/// # {
+/// use fmtlog::{Config, RotatingFile};
+///
+/// // logs/app-2021-01-01.log, logs/app-2021-01-02.log, ...
+/// let config = Config::new().output(RotatingFile::new("logs/app-%Y-%m-%d.log").daily());
+/// # }
+/// ```
+///
+/// Only files which can be parsed back by the patterns are removed:
+///
+/// ```rust
+/// # #[cfg(feature = "chrono")]
+/// # {
+/// use fmtlog::{Config, Logger, RotatingFile};
+/// use log::{Log, Record};
+///
+/// let dir = std::env::temp_dir().join(format!("fmtlog-doc-dated-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// for name in &["app-20200101.log", "app-20200102.log", "app-backup.log"] {
+///     std::fs::write(dir.join(name), "old\n").unwrap();
+/// }
+///
+/// let today = |t: chrono::DateTime<chrono::Local>| t.format("app-%Y%m%d.log").to_string();
+/// let before = today(chrono::Local::now());
+/// let logger = Logger::new(Config::new().format("%M\n").output(
+///     RotatingFile::new(dir.join("app-%Y%m%d.log"))
+///         .daily()
+///         .max_size(20)
+///         .keep(1),
+/// ));
+/// for i in 0..3 {
+///     logger.log(&Record::builder().args(format_args!("record #{}", i)).build());
+/// }
+/// drop(logger);
+///
+/// let after = today(chrono::Local::now());
+///
+/// let mut files: Vec<_> = std::fs::read_dir(&dir)
+///     .unwrap()
+///     .map(|e| e.unwrap().file_name().into_string().unwrap())
+///     .collect();
+/// files.sort();
+/// assert!(!files.iter().any(|f| f.starts_with("app-2020")));
+/// assert!(files.contains(&"app-backup.log".into()));
+/// // (The file may be rotated by the date at midnight.)
+/// if before == after {
+///     assert_eq!(files, [after.clone(), format!("{}.1", after), "app-backup.log".into()]);
+/// }
+/// # std::fs::remove_dir_all(dir).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RotatingFile {
+    pub(crate) path: path::PathBuf,
+    pub(crate) max_size: Option<u64>,
+    pub(crate) keep: Option<usize>,
+    pub(crate) max_total_size: Option<u64>,
+    pub(crate) max_age: Option<time::Duration>,
+    #[cfg(feature = "gzip")]
+    pub(crate) compress: bool,
+    #[cfg(feature = "chrono")]
+    pub(crate) period: Option<Period>,
+}
+
+/// The period of time-based rotation.
+#[cfg(feature = "chrono")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Period {
+    Hourly,
+    Daily,
+}
+
+impl RotatingFile {
+    /// Create a new instance. (The file is not rotated by default.)
+    pub fn new<T: Into<path::PathBuf>>(path: T) -> Self {
+        Self {
+            path: path.into(),
+            max_size: None,
+            keep: None,
+            max_total_size: None,
+            max_age: None,
+            #[cfg(feature = "gzip")]
+            compress: false,
+            #[cfg(feature = "chrono")]
+            period: None,
+        }
+    }
+
+    /// Rotate the file when it would exceed `bytes`.
+    ///
+    /// A log is never split, so a file may exceed the size
+    /// when one log is larger than it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Logger, RotatingFile};
+    /// use log::{Log, Record};
+    ///
+    /// let dir = std::env::temp_dir().join(format!("fmtlog-doc-size-{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir).unwrap();
+    ///
+    /// // Each log is 10 bytes.
+    /// let logger = Logger::new(Config::new().format("%M\n").output(
+    ///     RotatingFile::new(dir.join("app.log")).max_size(20).keep(2),
+    /// ));
+    /// for i in 0..7 {
+    ///     logger.log(&Record::builder().args(format_args!("record #{}", i)).build());
+    /// }
+    /// drop(logger);
+    ///
+    /// let read = |name| std::fs::read_to_string(dir.join(name)).unwrap();
+    /// assert_eq!(read("app.log"), "record #6\n");
+    /// assert_eq!(read("app.log.1"), "record #4\nrecord #5\n");
+    /// assert_eq!(read("app.log.2"), "record #2\nrecord #3\n");
+    /// assert!(!dir.join("app.log.3").exists());
+    /// # std::fs::remove_dir_all(dir).unwrap();
+    /// ```
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Set the number of rotated files to keep. (All files are kept by default.)
+    ///
+    /// The oldest files are removed on rotation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Logger, RotatingFile};
+    /// use log::{Log, Record};
+    ///
+    /// let dir = std::env::temp_dir().join(format!("fmtlog-doc-keep-{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir).unwrap();
+    ///
+    /// // The file is truncated instead of renaming with "keep(0)".
+    /// let logger = Logger::new(Config::new().format("%M\n").output(
+    ///     RotatingFile::new(dir.join("app.log")).max_size(20).keep(0),
+    /// ));
+    /// for i in 0..5 {
+    ///     logger.log(&Record::builder().args(format_args!("record #{}", i)).build());
+    /// }
+    /// drop(logger);
+    ///
+    /// let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+    /// assert_eq!(files.len(), 1);
+    /// assert_eq!(
+    ///     std::fs::read_to_string(dir.join("app.log")).unwrap(),
+    ///     "record #4\n"
+    /// );
+    /// # std::fs::remove_dir_all(dir).unwrap();
+    /// ```
+    pub fn keep(mut self, count: usize) -> Self {
+        self.keep = Some(count);
+        self
+    }
+
+    /// Remove the oldest rotated files while their total size exceeds `bytes`.
+    ///
+    /// The current file is not counted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Logger, RotatingFile};
+    /// use log::{Log, Record};
+    ///
+    /// let dir = std::env::temp_dir().join(format!("fmtlog-doc-total-{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir).unwrap();
+    ///
+    /// let logger = Logger::new(Config::new().format("%M\n").output(
+    ///     RotatingFile::new(dir.join("app.log"))
+    ///         .max_size(20)
+    ///         .max_total_size(30),
+    /// ));
+    /// for i in 0..7 {
+    ///     logger.log(&Record::builder().args(format_args!("record #{}", i)).build());
+    /// }
+    /// // Wait for the background thread.
+    /// drop(logger);
+    ///
+    /// // "app.log.2" (40 bytes in total) and older files are removed.
+    /// let mut files: Vec<_> = std::fs::read_dir(&dir)
+    ///     .unwrap()
+    ///     .map(|e| e.unwrap().file_name().into_string().unwrap())
+    ///     .collect();
+    /// files.sort();
+    /// assert_eq!(files, ["app.log", "app.log.1"]);
+    /// # std::fs::remove_dir_all(dir).unwrap();
+    /// ```
+    pub fn max_total_size(mut self, bytes: u64) -> Self {
+        self.max_total_size = Some(bytes);
+        self
+    }
+
+    /// Remove rotated files older than `age`. (By the last modified time)
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Logger, RotatingFile};
+    /// use log::{Log, Record};
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+    ///
+    /// let dir = std::env::temp_dir().join(format!("fmtlog-doc-age-{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir).unwrap();
+    ///
+    /// // Rotated 10 days ago.
+    /// let old = std::fs::File::create(dir.join("app.log.1")).unwrap();
+    /// old.set_modified(SystemTime::now() - 10 * DAY).unwrap();
+    ///
+    /// let logger = Logger::new(Config::new().format("%M\n").output(
+    ///     RotatingFile::new(dir.join("app.log"))
+    ///         .max_size(20)
+    ///         .max_age(DAY),
+    /// ));
+    /// for i in 0..3 {
+    ///     logger.log(&Record::builder().args(format_args!("record #{}", i)).build());
+    /// }
+    /// drop(logger);
+    ///
+    /// // The old file was renamed to "app.log.2" and removed.
+    /// assert!(!dir.join("app.log.2").exists());
+    /// assert_eq!(
+    ///     std::fs::read_to_string(dir.join("app.log.1")).unwrap(),
+    ///     "record #0\nrecord #1\n"
+    /// );
+    /// # std::fs::remove_dir_all(dir).unwrap();
+    /// ```
+    pub fn max_age(mut self, age: time::Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+
+    /// Compress rotated files to `<file>.gz`. (Requires feature `gzip`)
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Logger, RotatingFile};
+    /// use log::{Log, Record};
+    /// use std::io::Read;
+    ///
+    /// let dir = std::env::temp_dir().join(format!("fmtlog-doc-gzip-{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir).unwrap();
+    ///
+    /// let logger = Logger::new(Config::new().format("%M\n").output(
+    ///     RotatingFile::new(dir.join("app.log"))
+    ///         .max_size(20)
+    ///         .keep(2)
+    ///         .compress(true),
+    /// ));
+    /// for i in 0..7 {
+    ///     logger.log(&Record::builder().args(format_args!("record #{}", i)).build());
+    /// }
+    /// drop(logger);
+    ///
+    /// let mut files: Vec<_> = std::fs::read_dir(&dir)
+    ///     .unwrap()
+    ///     .map(|e| e.unwrap().file_name().into_string().unwrap())
+    ///     .collect();
+    /// files.sort();
+    /// assert_eq!(files, ["app.log", "app.log.1.gz", "app.log.2.gz"]);
+    ///
+    /// let mut text = String::new();
+    /// let file = std::fs::File::open(dir.join("app.log.1.gz")).unwrap();
+    /// flate2::read::GzDecoder::new(file).read_to_string(&mut text).unwrap();
+    /// assert_eq!(text, "record #4\nrecord #5\n");
+    /// # std::fs::remove_dir_all(dir).unwrap();
+    /// ```
+    #[cfg(feature = "gzip")]
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Rotate the file at the start of each day. (Requires feature `chrono`)
+    ///
+    /// An existing file written before today is rotated on the first write.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Logger, RotatingFile};
+    /// use log::{Log, Record};
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let dir = std::env::temp_dir().join(format!("fmtlog-doc-daily-{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir).unwrap();
+    ///
+    /// // Written two days ago.
+    /// std::fs::write(dir.join("app.log"), "old\n").unwrap();
+    /// std::fs::File::options()
+    ///     .write(true)
+    ///     .open(dir.join("app.log"))
+    ///     .unwrap()
+    ///     .set_modified(SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60))
+    ///     .unwrap();
+    ///
+    /// let logger = Logger::new(
+    ///     Config::new()
+    ///         .format("%M\n")
+    ///         .output(RotatingFile::new(dir.join("app.log")).daily()),
+    /// );
+    /// logger.log(&Record::builder().args(format_args!("new")).build());
+    /// drop(logger);
+    ///
+    /// let read = |name| std::fs::read_to_string(dir.join(name)).unwrap();
+    /// assert_eq!(read("app.log.1"), "old\n");
+    /// assert_eq!(read("app.log"), "new\n");
+    /// # std::fs::remove_dir_all(dir).unwrap();
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn daily(mut self) -> Self {
+        self.period = Some(Period::Daily);
+        self
+    }
+
+    /// Rotate the file at the start of each hour. (Requires feature `chrono`)
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Logger, RotatingFile};
+    /// use log::{Log, Record};
+    ///
+    /// let dir = std::env::temp_dir().join(format!("fmtlog-doc-hourly-{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir).unwrap();
+    ///
+    /// let hour = || chrono::Local::now().format("app-%Y%m%d-%H.log").to_string();
+    /// let before = hour();
+    /// let logger = Logger::new(
+    ///     Config::new()
+    ///         .format("%M\n")
+    ///         .output(RotatingFile::new(dir.join("app-%Y%m%d-%H.log")).hourly()),
+    /// );
+    /// logger.log(&Record::builder().args(format_args!("Hello!")).build());
+    /// drop(logger);
+    /// let after = hour();
+    ///
+    /// // The name is of the current hour. (Or the previous one at the start of an hour.)
+    /// let name = if dir.join(&after).exists() { after } else { before };
+    /// assert_eq!(std::fs::read_to_string(dir.join(name)).unwrap(), "Hello!\n");
+    /// # std::fs::remove_dir_all(dir).unwrap();
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn hourly(mut self) -> Self {
+        self.period = Some(Period::Hourly);
+        self
+    }
+}
@@ -0,0 +1,29 @@
+use crate::{FormatError, Output};
+use std::{fmt, io};
+
+/// The error type of fmtlog.
+#[derive(Debug)]
+pub enum Error {
+    /// The format string is invalid.
+    Format(FormatError),
+    /// Failed to open the output.
+    Open(Output, io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Format(e) => write!(fmt, "Invalid format: {}", e),
+            Self::Open(output, e) => write!(fmt, "Failed to open {}: {}", output, e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Format(e) => Some(e),
+            Self::Open(_, e) => Some(e),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use super::{FormatError, Source};
 use colored::Color as Orig;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -9,9 +10,9 @@ impl From<Orig> for Color {
     }
 }
 
-impl Into<Orig> for Color {
-    fn into(self) -> Orig {
-        self.0
+impl From<Color> for Orig {
+    fn from(c: Color) -> Self {
+        c.0
     }
 }
 
@@ -64,10 +65,20 @@ impl std::str::FromStr for Color {
 }
 
 impl Color {
-    pub fn parse_until<T: Iterator<Item = char>>(iter: &mut T, ch: char) -> Result<Self, String> {
+    pub fn parse_until(
+        source: &mut Source,
+        ch: char,
+        specifier: char,
+    ) -> Result<Self, FormatError> {
         use std::str::FromStr;
 
-        let s: String = iter.take_while(|c| *c != ch).collect();
-        Self::from_str(&s)
+        let expected = match ch {
+            ',' => "','",
+            _ => "')'",
+        };
+
+        let position = source.position();
+        let s = source.take_until(ch, specifier, expected)?;
+        Self::from_str(&s).map_err(|_| source.invalid_color(position, specifier, s))
     }
 }
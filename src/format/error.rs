@@ -0,0 +1,171 @@
+use std::fmt;
+
+/// An error of the format string.
+///
+/// # Example
+///
+/// ```rust
+/// use fmtlog::{Config, Error, Logger};
+///
+/// match Logger::try_new(Config::new().format("[%Q] %M\n")) {
+///     Err(Error::Format(e)) => {
+///         assert_eq!(e.position(), 2);
+///         assert_eq!(e.specifier(), Some('Q'));
+///         assert_eq!(
+///             e.to_string(),
+///             "Invalid specifier \"%Q\".\n  [%Q] %M\\n\n    ^"
+///         );
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FormatError {
+    /// The format string ended unexpectedly.
+    UnexpectedEnd {
+        format: String,
+        position: usize,
+        specifier: Option<char>,
+        expected: &'static str,
+    },
+    /// An unexpected character was found.
+    UnexpectedChar {
+        format: String,
+        position: usize,
+        specifier: Option<char>,
+        found: char,
+        expected: &'static str,
+    },
+    /// The specifier is unknown.
+    InvalidSpecifier {
+        format: String,
+        position: usize,
+        specifier: char,
+    },
+    /// The color is unknown.
+    InvalidColor {
+        format: String,
+        position: usize,
+        specifier: char,
+        color: String,
+    },
+    /// The argument of the specifier is unknown.
+    InvalidArgument {
+        format: String,
+        position: usize,
+        specifier: char,
+        argument: String,
+    },
+    /// The width or the precision is too large.
+    TooLarge {
+        format: String,
+        position: usize,
+        number: String,
+        max: usize,
+    },
+}
+
+impl FormatError {
+    /// The format string which has the error.
+    pub fn format(&self) -> &str {
+        match self {
+            Self::UnexpectedEnd { format, .. }
+            | Self::UnexpectedChar { format, .. }
+            | Self::InvalidSpecifier { format, .. }
+            | Self::InvalidColor { format, .. }
+            | Self::InvalidArgument { format, .. }
+            | Self::TooLarge { format, .. } => format,
+        }
+    }
+
+    /// The byte offset of the error in the format string.
+    pub fn position(&self) -> usize {
+        match self {
+            Self::UnexpectedEnd { position, .. }
+            | Self::UnexpectedChar { position, .. }
+            | Self::InvalidSpecifier { position, .. }
+            | Self::InvalidColor { position, .. }
+            | Self::InvalidArgument { position, .. }
+            | Self::TooLarge { position, .. } => *position,
+        }
+    }
+
+    /// The specifier which has the error.
+    pub fn specifier(&self) -> Option<char> {
+        match self {
+            Self::UnexpectedEnd { specifier, .. } | Self::UnexpectedChar { specifier, .. } => {
+                *specifier
+            }
+            Self::InvalidSpecifier { specifier, .. }
+            | Self::InvalidColor { specifier, .. }
+            | Self::InvalidArgument { specifier, .. } => Some(*specifier),
+            Self::TooLarge { .. } => None,
+        }
+    }
+
+    /// The expected token.
+    pub fn expected(&self) -> Option<&'static str> {
+        match self {
+            Self::UnexpectedEnd { expected, .. } | Self::UnexpectedChar { expected, .. } => {
+                Some(expected)
+            }
+            Self::InvalidSpecifier { .. }
+            | Self::InvalidColor { .. }
+            | Self::InvalidArgument { .. }
+            | Self::TooLarge { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let context = match self.specifier() {
+            Some(c) => format!(" in \"%{}\"", c),
+            None => String::new(),
+        };
+
+        match self {
+            Self::UnexpectedEnd { expected, .. } => {
+                write!(fmt, "Unexpected end{}, expected {}.", context, expected)?
+            }
+            Self::UnexpectedChar {
+                found, expected, ..
+            } => write!(
+                fmt,
+                "Unexpected {:?}{}, expected {}.",
+                found, context, expected
+            )?,
+            Self::InvalidSpecifier { specifier, .. } => {
+                write!(fmt, "Invalid specifier \"%{}\".", specifier)?
+            }
+            Self::InvalidColor { color, .. } => {
+                write!(fmt, "Invalid color {:?}{}.", color, context)?
+            }
+            Self::InvalidArgument { argument, .. } => {
+                write!(fmt, "Invalid argument {:?}{}.", argument, context)?
+            }
+            Self::TooLarge { number, max, .. } => {
+                write!(fmt, "Too large number {}, expected up to {}.", number, max)?
+            }
+        }
+
+        // Show the format string with a caret under the error.
+        // (Escape newlines, etc. to keep the caret aligned.)
+        let format = self.format();
+        let position = self.position().min(format.len());
+        let column: usize = format[..position]
+            .chars()
+            .map(|c| c.escape_debug().count())
+            .sum();
+
+        write!(
+            fmt,
+            "\n  {}\n  {}^",
+            format.escape_debug(),
+            " ".repeat(column)
+        )
+    }
+}
+
+impl std::error::Error for FormatError {}
@@ -0,0 +1,122 @@
+use std::fmt::Write;
+
+/// The syntax to escape the text for. (e.g. `%E(json){...}`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Escape {
+    /// The content of a JSON string.
+    Json,
+    /// The content of a TOML basic string.
+    Toml,
+    /// The content of a YAML double-quoted string.
+    Yaml,
+    /// A CSV field. (Quoted if needed.)
+    Csv,
+    /// A logfmt value. (Quoted if needed.)
+    Logfmt,
+}
+
+impl std::str::FromStr for Escape {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "json" | "JSON" => Ok(Self::Json),
+            "toml" | "TOML" => Ok(Self::Toml),
+            "yaml" | "YAML" => Ok(Self::Yaml),
+            "csv" | "CSV" => Ok(Self::Csv),
+            "logfmt" => Ok(Self::Logfmt),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Escape {
+    pub fn apply(&self, s: &str) -> String {
+        match self {
+            Self::Json => json(s),
+            Self::Toml => toml(s),
+            Self::Yaml => yaml(s),
+            Self::Csv => csv(s),
+            Self::Logfmt => logfmt(s),
+        }
+    }
+}
+
+/// Escape the string for JSON. (Without quotes.)
+pub fn json(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            '\u{08}' => res.push_str("\\b"),
+            '\u{0c}' => res.push_str("\\f"),
+            c if c.is_control() => {
+                // Writing to "String" never fails.
+                let _ = write!(res, "\\u{:04x}", c as u32);
+            }
+            c => res.push(c),
+        }
+    }
+
+    res
+}
+
+/// Escape the string for TOML basic strings. (Without quotes.)
+pub fn toml(s: &str) -> String {
+    // TOML basic strings have the same escape sequences as JSON.
+    // (Including "\u007f", which is a control character.)
+    json(s)
+}
+
+/// Escape the string for YAML double-quoted strings. (Without quotes.)
+pub fn yaml(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+
+    for c in json(s).chars() {
+        match c {
+            // YAML treats them as line breaks.
+            '\u{2028}' => res.push_str("\\L"),
+            '\u{2029}' => res.push_str("\\P"),
+            c => res.push(c),
+        }
+    }
+
+    res
+}
+
+/// Quote the string as a CSV field if needed. (RFC 4180)
+pub fn csv(s: &str) -> String {
+    if s.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Quote the string as a logfmt value if needed.
+pub fn logfmt(s: &str) -> String {
+    if s.is_empty() || s.contains(|c: char| c <= ' ' || c == '=' || c == '"' || c.is_control()) {
+        format!("\"{}\"", json(s))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Make the string a valid logfmt key. (An empty key becomes "_".)
+#[cfg(feature = "kv")]
+pub fn logfmt_key(s: &str) -> String {
+    if s.is_empty() {
+        return String::from("_");
+    }
+
+    s.chars()
+        .map(|c| match c {
+            c if c <= ' ' || c == '=' || c == '"' || c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
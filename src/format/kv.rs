@@ -0,0 +1,19 @@
+use log::kv::{Error, Key, Value, VisitSource};
+use log::Record;
+
+/// Collect the key-values of the record.
+pub fn pairs(record: &Record) -> Vec<(String, String)> {
+    struct Collect(Vec<(String, String)>);
+
+    impl<'kvs> VisitSource<'kvs> for Collect {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+            self.0.push((key.to_string(), value.to_string()));
+            Ok(())
+        }
+    }
+
+    let mut collect = Collect(Vec::new());
+    // "Collect" never fails.
+    let _ = record.key_values().visit(&mut collect);
+    collect.0
+}
@@ -1,7 +1,23 @@
 #[cfg(feature = "colored")]
 mod color;
+mod error;
+mod escape;
+#[cfg(feature = "kv")]
+pub(crate) mod kv;
+mod modifier;
 #[cfg(feature = "colored")]
 mod pallet;
+pub(crate) mod process;
+mod render;
+mod source;
+pub(crate) mod syslog;
+mod thread;
+
+pub use error::FormatError;
+use escape::Escape;
+use modifier::Modifier;
+use render::Renderer;
+use source::Source;
 
 #[cfg(feature = "colored")]
 use color::Color;
@@ -16,18 +32,52 @@ use chrono::{Local, Utc};
 use log::Record;
 use std::io;
 
-/// The format structure.
+/// A parsed format string.
+///
+/// This type formats records like the logger does.
+/// (See [the format specification](index.html#format-specification).)
+///
+/// # Example
+///
+/// ```rust
+/// use fmtlog::Format;
+/// use log::{Level, Record};
+///
+/// let format = Format::new("[%L] %M (%N)").unwrap();
+/// let record = Record::builder()
+///     .args(format_args!("Hello!"))
+///     .level(Level::Info)
+///     .target("my_app")
+///     .build();
+///
+/// assert_eq!(format.render(&record, false), "[INFO] Hello! (my_app)");
+/// ```
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub(crate) struct Format(Vec<Element>);
+pub struct Format(Vec<Element>);
+
+impl std::str::FromStr for Format {
+    type Err = FormatError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
 
 impl Format {
-    pub(crate) fn new<T: AsRef<str>>(s: T) -> Result<Self, String> {
-        Self::parse(&mut s.as_ref().chars())
+    /// Parse the format string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::Format;
+    ///
+    /// assert!(Format::new("%L: %M\n").is_ok());
+    /// assert!(Format::new("%L: %Q\n").is_err());
+    /// ```
+    pub fn new<T: AsRef<str>>(s: T) -> Result<Self, FormatError> {
+        Self::parse(&mut Source::new(s.as_ref()))
     }
 
-    // This function is not used when feature "colored" is disabled.
-    #[allow(dead_code)]
-    fn parse_until<T: Iterator<Item = char>>(s: &mut T, ch: char) -> Result<Self, String> {
+    fn parse_until(s: &mut Source, ch: char, specifier: char) -> Result<Self, FormatError> {
         let mut res = Vec::new();
 
         // Temporary string holder.
@@ -41,7 +91,7 @@ impl Format {
                     res.push(Element::Const(const_str.clone()));
                     const_str.clear();
                     // Parse an specifier.
-                    res.push(Element::Special(Special::parse(s)?));
+                    res.push(Element::parse(s)?);
                 }
                 Some(c) if c == ch => {
                     // Add "const_str" to "res".
@@ -50,17 +100,13 @@ impl Format {
                 }
                 // Add a character to "const_str".
                 Some(c) => const_str.push(c),
-                None => {
-                    // Add "const_str" to "res".
-                    res.push(Element::Const(const_str));
-                    break;
-                }
+                None => return Err(s.unexpected_end(Some(specifier), "'}'")),
             };
         }
         Ok(Format(res))
     }
 
-    fn parse<T: Iterator<Item = char>>(s: &mut T) -> Result<Self, String> {
+    fn parse(s: &mut Source) -> Result<Self, FormatError> {
         let mut res = Vec::new();
 
         // Temporary string holder.
@@ -74,7 +120,7 @@ impl Format {
                     res.push(Element::Const(const_str.clone()));
                     const_str.clear();
                     // Parse an specifier.
-                    res.push(Element::Special(Special::parse(s)?));
+                    res.push(Element::parse(s)?);
                 }
                 // Add a character to "const_str".
                 Some(c) => const_str.push(c),
@@ -88,50 +134,136 @@ impl Format {
         Ok(Format(res))
     }
 
-    pub(crate) fn write<W: io::Write>(
+    // Resolve values which are slow to get.
+    pub(crate) fn init(&self) {
+        process::hostname();
+        process::program();
+    }
+
+    /// Write the formatted record to the writer.
+    ///
+    /// The text is colorized when `colorize` is `true` and feature `colored` is enabled.
+    /// Each part of the format is written separately, so use a buffered writer
+    /// or [`Format::render`](#method.render) to write a record at once.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::Format;
+    /// use log::{Level, Record};
+    ///
+    /// let format = Format::new("%l: %M\n").unwrap();
+    /// let record = Record::builder()
+    ///     .args(format_args!("Something has failed."))
+    ///     .level(Level::Error)
+    ///     .build();
+    ///
+    /// let mut buf = Vec::new();
+    /// format.write_to(&mut buf, &record, false).unwrap();
+    ///
+    /// assert_eq!(buf, b"error: Something has failed.\n");
+    /// ```
+    pub fn write_to<W: io::Write>(
         &self,
         writer: &mut W,
         record: &Record,
         colorize: bool,
     ) -> io::Result<()> {
+        self.write(
+            writer,
+            &Context {
+                record,
+                colorize,
+                #[cfg(feature = "kv")]
+                pair: None,
+            },
+        )
+    }
+
+    fn write<W: io::Write>(&self, writer: &mut W, ctx: &Context) -> io::Result<()> {
         for elem in self.0.iter() {
             // Write each elements.
-            elem.write(writer, record, colorize)?;
+            elem.write(writer, ctx)?;
         }
 
         Ok(())
     }
 
-    #[allow(dead_code)]
-    fn to_str(&self, record: &Record, colorize: bool) -> io::Result<String> {
+    /// Format the record to a string.
+    ///
+    /// The text is colorized when `colorize` is `true` and feature `colored` is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::Format;
+    /// use log::{Level, Record};
+    ///
+    /// let format = Format::new("%L - %M").unwrap();
+    /// let record = Record::builder()
+    ///     .args(format_args!("Hello!"))
+    ///     .level(Level::Warn)
+    ///     .build();
+    ///
+    /// assert_eq!(format.render(&record, false), "WARN - Hello!");
+    /// ```
+    pub fn render(&self, record: &Record, colorize: bool) -> String {
+        // Writing to "Vec<u8>" fails only when a "Display" implementation fails.
+        let mut buf: Vec<u8> = Vec::new();
+        match self.write_to(&mut buf, record, colorize) {
+            Ok(()) => String::from_utf8(buf).unwrap(),
+            Err(_) => String::new(),
+        }
+    }
+
+    fn to_str(&self, ctx: &Context) -> io::Result<String> {
         // Write to Vec<u8>
         let mut buf: Vec<u8> = Vec::new();
-        self.write(&mut buf, record, colorize)?;
+        self.write(&mut buf, ctx)?;
         // Convert to UTF-8
         Ok(String::from_utf8(buf).unwrap())
     }
 }
 
+/// The values to format.
+struct Context<'a, 'r> {
+    record: &'a Record<'r>,
+    colorize: bool,
+    // The key-value pair in "%K{...}".
+    #[cfg(feature = "kv")]
+    pair: Option<(&'a str, &'a str)>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum Element {
     /// A Const String
     Const(String),
     /// A Specifier
     Special(Special),
+    /// A Specifier with the width and the precision
+    Modified(Modifier, Special),
 }
 
 impl Element {
-    fn write<W: io::Write>(
-        &self,
-        writer: &mut W,
-        record: &Record,
-        colorize: bool,
-    ) -> io::Result<()> {
+    fn parse(s: &mut Source) -> Result<Self, FormatError> {
+        Ok(match Modifier::parse(s)? {
+            Some(m) => Self::Modified(m, Special::parse(s)?),
+            None => Self::Special(Special::parse(s)?),
+        })
+    }
+
+    fn write<W: io::Write>(&self, writer: &mut W, ctx: &Context) -> io::Result<()> {
         match self {
             // Write the string.
             Self::Const(s) => write!(writer, "{}", s),
             // Process the specifier.
-            Self::Special(spec) => spec.write(writer, record, colorize),
+            Self::Special(spec) => spec.write(writer, ctx),
+            // Process the specifier and align it.
+            Self::Modified(modifier, spec) => {
+                let mut buf = Vec::new();
+                spec.write(&mut buf, ctx)?;
+                write!(writer, "{}", modifier.apply(&String::from_utf8_lossy(&buf)))
+            }
         }
     }
 }
@@ -147,6 +279,20 @@ enum Special {
     Message,
     LogLevelLower,
     LogLevelUpper,
+    ThreadName,
+    ThreadId,
+    ProcessId,
+    HostName,
+    ProgramName,
+    Render(Renderer),
+    Escape(Escape, Format),
+
+    #[cfg(feature = "kv")]
+    KeyValues(Option<String>, Option<Format>),
+    #[cfg(feature = "kv")]
+    Key,
+    #[cfg(feature = "kv")]
+    Value,
 
     #[cfg(feature = "chrono")]
     Time(String),
@@ -176,11 +322,11 @@ enum Special {
 }
 
 impl Special {
-    fn parse<T: Iterator<Item = char>>(s: &mut T) -> Result<Self, String> {
+    fn parse(s: &mut Source) -> Result<Self, FormatError> {
         // Detect the specifier type.
         let kind = match s.next() {
             Some(c) => c,
-            None => return Err(String::from("Unnexpected end.")),
+            None => return Err(s.unexpected_end(None, "a specifier")),
         };
 
         match kind {
@@ -193,25 +339,78 @@ impl Special {
             'M' => Ok(Self::Message),
             'l' => Ok(Self::LogLevelLower),
             'L' => Ok(Self::LogLevelUpper),
+            't' => Ok(Self::ThreadName),
+            'I' => Ok(Self::ThreadId),
+            'P' => Ok(Self::ProcessId),
+            'H' => Ok(Self::HostName),
+            'p' => Ok(Self::ProgramName),
+
+            'R' => {
+                use std::str::FromStr;
+
+                s.expect('(', kind, "'(' and a renderer")?;
+                let position = s.position();
+                let name = s.take_until(')', kind, "')'")?;
+                let renderer = Renderer::from_str(&name)
+                    .map_err(|_| s.invalid_argument(position, kind, name))?;
+
+                Ok(Self::Render(renderer))
+            }
+
+            'E' => {
+                use std::str::FromStr;
+
+                s.expect('(', kind, "'(' and a syntax")?;
+                let position = s.position();
+                let name = s.take_until(')', kind, "')'")?;
+                let escape = Escape::from_str(&name)
+                    .map_err(|_| s.invalid_argument(position, kind, name))?;
+
+                s.expect('{', kind, "'{' and the body")?;
+
+                // Parse the body.
+                let format = Format::parse_until(s, '}', kind)?;
+
+                Ok(Self::Escape(escape, format))
+            }
+
+            #[cfg(feature = "kv")]
+            'K' => {
+                // Parse the key.
+                let key = if s.peek() == Some('(') {
+                    s.next();
+                    Some(s.take_until(')', kind, "')'")?)
+                } else {
+                    None
+                };
+
+                // Parse the body.
+                let format = if s.peek() == Some('{') {
+                    s.next();
+                    Some(Format::parse_until(s, '}', kind)?)
+                } else {
+                    None
+                };
+
+                Ok(Self::KeyValues(key, format))
+            }
+            #[cfg(feature = "kv")]
+            'k' => Ok(Self::Key),
+            #[cfg(feature = "kv")]
+            'v' => Ok(Self::Value),
 
             #[cfg(feature = "chrono")]
             'T' => {
-                if s.next() != Some('(') {
-                    return Err(String::from("Missing time format."));
-                }
-
-                let format = s.take_while(|c| *c != ')').collect();
+                s.expect('(', kind, "'(' and a time format")?;
+                let format = s.take_until(')', kind, "')'")?;
 
                 Ok(Self::Time(format))
             }
 
             #[cfg(feature = "chrono")]
             'U' => {
-                if s.next() != Some('(') {
-                    return Err(String::from("Missing time format."));
-                }
-
-                let format = s.take_while(|c| *c != ')').collect();
+                s.expect('(', kind, "'(' and a time format")?;
+                let format = s.take_until(')', kind, "')'")?;
 
                 Ok(Self::UtcTime(format))
             }
@@ -220,37 +419,35 @@ impl Special {
             'F' => {
                 use std::str::FromStr;
 
-                if s.next() != Some('(') {
-                    return Err(String::from("Missing color specifier."));
-                }
+                s.expect('(', kind, "'(' and a color")?;
 
                 // Parse the first color and detect color numbers.
+                let position = s.position();
                 let mut color = String::new();
                 let branch = loop {
                     match s.next() {
                         Some(')') => break false,
                         Some(',') => break true,
                         Some(c) => color.push(c),
-                        None => return Err(String::from("Unnexpected end.")),
+                        None => return Err(s.unexpected_end(Some(kind), "')' or ','")),
                     }
                 };
 
-                let color = Color::from_str(&color)?;
+                let color =
+                    Color::from_str(&color).map_err(|_| s.invalid_color(position, kind, color))?;
 
                 if branch {
                     // Read five colors.
                     let error = color;
-                    let warn = Color::parse_until(s, ',')?;
-                    let info = Color::parse_until(s, ',')?;
-                    let debug = Color::parse_until(s, ',')?;
-                    let trace = Color::parse_until(s, ')')?;
+                    let warn = Color::parse_until(s, ',', kind)?;
+                    let info = Color::parse_until(s, ',', kind)?;
+                    let debug = Color::parse_until(s, ',', kind)?;
+                    let trace = Color::parse_until(s, ')', kind)?;
 
-                    if s.next() != Some('{') {
-                        return Err(String::from("Missing the body."));
-                    }
+                    s.expect('{', kind, "'{' and the body")?;
 
                     // Parse the body.
-                    let format = Format::parse_until(s, '}')?;
+                    let format = Format::parse_until(s, '}', kind)?;
 
                     Ok(Self::FgColorBranch(
                         Pallet {
@@ -263,12 +460,10 @@ impl Special {
                         format,
                     ))
                 } else {
-                    if s.next() != Some('{') {
-                        return Err(String::from("Missing the body."));
-                    }
+                    s.expect('{', kind, "'{' and the body")?;
 
                     // Parse the body.
-                    let format = Format::parse_until(s, '}')?;
+                    let format = Format::parse_until(s, '}', kind)?;
 
                     Ok(Self::FgColor(color, format))
                 }
@@ -277,37 +472,35 @@ impl Special {
             'B' => {
                 use std::str::FromStr;
 
-                if s.next() != Some('(') {
-                    return Err(String::from("Missing color specifier."));
-                }
+                s.expect('(', kind, "'(' and a color")?;
 
                 // Parse the first color and detect color numbers.
+                let position = s.position();
                 let mut color = String::new();
                 let branch = loop {
                     match s.next() {
                         Some(')') => break false,
                         Some(',') => break true,
                         Some(c) => color.push(c),
-                        None => return Err(String::from("Unnexpected end.")),
+                        None => return Err(s.unexpected_end(Some(kind), "')' or ','")),
                     }
                 };
 
-                let color = Color::from_str(&color)?;
+                let color =
+                    Color::from_str(&color).map_err(|_| s.invalid_color(position, kind, color))?;
 
                 if branch {
                     // Read five colors.
                     let error = color;
-                    let warn = Color::parse_until(s, ',')?;
-                    let info = Color::parse_until(s, ',')?;
-                    let debug = Color::parse_until(s, ',')?;
-                    let trace = Color::parse_until(s, ')')?;
+                    let warn = Color::parse_until(s, ',', kind)?;
+                    let info = Color::parse_until(s, ',', kind)?;
+                    let debug = Color::parse_until(s, ',', kind)?;
+                    let trace = Color::parse_until(s, ')', kind)?;
 
-                    if s.next() != Some('{') {
-                        return Err(String::from("Missing the body."));
-                    }
+                    s.expect('{', kind, "'{' and the body")?;
 
                     // Parse the body.
-                    let format = Format::parse_until(s, '}')?;
+                    let format = Format::parse_until(s, '}', kind)?;
 
                     Ok(Self::BgColorBranch(
                         Pallet {
@@ -320,94 +513,78 @@ impl Special {
                         format,
                     ))
                 } else {
-                    if s.next() != Some('{') {
-                        return Err(String::from("Missing the body."));
-                    }
+                    s.expect('{', kind, "'{' and the body")?;
 
                     // Parse the body.
-                    let format = Format::parse_until(s, '}')?;
+                    let format = Format::parse_until(s, '}', kind)?;
 
                     Ok(Self::BgColor(color, format))
                 }
             }
             #[cfg(feature = "colored")]
             'b' => {
-                if s.next() != Some('{') {
-                    return Err(String::from("Missing the body."));
-                }
+                s.expect('{', kind, "'{' and the body")?;
 
                 // Parse the body.
-                let format = Format::parse_until(s, '}')?;
+                let format = Format::parse_until(s, '}', kind)?;
 
                 Ok(Self::Bold(format))
             }
             #[cfg(feature = "colored")]
             'd' => {
-                if s.next() != Some('{') {
-                    return Err(String::from("Missing the body."));
-                }
+                s.expect('{', kind, "'{' and the body")?;
 
                 // Parse the body.
-                let format = Format::parse_until(s, '}')?;
+                let format = Format::parse_until(s, '}', kind)?;
 
                 Ok(Self::Dimmed(format))
             }
             #[cfg(feature = "colored")]
             'i' => {
-                if s.next() != Some('{') {
-                    return Err(String::from("Missing the body."));
-                }
+                s.expect('{', kind, "'{' and the body")?;
 
                 // Parse the body.
-                let format = Format::parse_until(s, '}')?;
+                let format = Format::parse_until(s, '}', kind)?;
 
                 Ok(Self::Italic(format))
             }
             #[cfg(feature = "colored")]
             'r' => {
-                if s.next() != Some('{') {
-                    return Err(String::from("Missing the body."));
-                }
+                s.expect('{', kind, "'{' and the body")?;
 
                 // Parse the body.
-                let format = Format::parse_until(s, '}')?;
+                let format = Format::parse_until(s, '}', kind)?;
 
                 Ok(Self::Reversed(format))
             }
             #[cfg(feature = "colored")]
             'u' => {
-                if s.next() != Some('{') {
-                    return Err(String::from("Missing the body."));
-                }
+                s.expect('{', kind, "'{' and the body")?;
 
                 // Parse the body.
-                let format = Format::parse_until(s, '}')?;
+                let format = Format::parse_until(s, '}', kind)?;
 
                 Ok(Self::Underline(format))
             }
             #[cfg(feature = "colored")]
             's' => {
-                if s.next() != Some('{') {
-                    return Err(String::from("Missing the body."));
-                }
+                s.expect('{', kind, "'{' and the body")?;
 
                 // Parse the body.
-                let format = Format::parse_until(s, '}')?;
+                let format = Format::parse_until(s, '}', kind)?;
 
                 Ok(Self::StrikeThrough(format))
             }
-            _ => Err(String::from("Invalid specifier.")),
+            _ => Err(s.invalid_specifier(kind)),
         }
     }
 
-    // Argument "colorize" is not used when feature "colored" is disabled.
-    #[allow(unused_variables)]
-    fn write<W: io::Write>(
-        &self,
-        writer: &mut W,
-        record: &Record,
-        colorize: bool,
-    ) -> io::Result<()> {
+    fn write<W: io::Write>(&self, writer: &mut W, ctx: &Context) -> io::Result<()> {
+        let record = ctx.record;
+        // "colorize" is not used when feature "colored" is disabled.
+        #[allow(unused_variables)]
+        let colorize = ctx.colorize;
+
         match self {
             Self::Percent => write!(writer, "%"),
             Self::Close => write!(writer, "}}"),
@@ -424,6 +601,54 @@ impl Special {
             Self::Message => write!(writer, "{}", record.args()),
             Self::LogLevelUpper => write!(writer, "{}", record.level()),
             Self::LogLevelLower => write!(writer, "{}", record.level().to_string().to_lowercase()),
+            Self::ThreadName => write!(writer, "{}", thread::name()),
+            Self::ThreadId => write!(writer, "{}", thread::id()),
+            Self::ProcessId => write!(writer, "{}", process::pid()),
+            Self::HostName => write!(writer, "{}", process::hostname()),
+            Self::ProgramName => write!(writer, "{}", process::program()),
+            Self::Render(renderer) => renderer.write(writer, record),
+            Self::Escape(escape, format) => {
+                let s = format.to_str(ctx)?;
+                write!(writer, "{}", escape.apply(&s))
+            }
+
+            #[cfg(feature = "kv")]
+            Self::KeyValues(key, format) => {
+                let pairs = kv::pairs(record);
+                let mut pairs = pairs
+                    .iter()
+                    .filter(|p| key.as_ref().is_none_or(|k| *k == p.0));
+
+                match format {
+                    // "%K(<key>)"
+                    None if key.is_some() => match pairs.next() {
+                        Some((_, v)) => write!(writer, "{}", v),
+                        None => Ok(()),
+                    },
+                    // "%K"
+                    None => {
+                        let pairs: Vec<_> = pairs.map(|(k, v)| format!("{}={}", k, v)).collect();
+                        write!(writer, "{}", pairs.join(" "))
+                    }
+                    // "%K{...}" or "%K(<key>){...}"
+                    Some(format) => {
+                        for (k, v) in pairs {
+                            format.write(
+                                writer,
+                                &Context {
+                                    pair: Some((k, v)),
+                                    ..*ctx
+                                },
+                            )?;
+                        }
+                        Ok(())
+                    }
+                }
+            }
+            #[cfg(feature = "kv")]
+            Self::Key => write!(writer, "{}", ctx.pair.map(|p| p.0).unwrap_or_default()),
+            #[cfg(feature = "kv")]
+            Self::Value => write!(writer, "{}", ctx.pair.map(|p| p.1).unwrap_or_default()),
 
             #[cfg(feature = "chrono")]
             Self::Time(format) => write!(writer, "{}", Local::now().format(format)),
@@ -432,7 +657,7 @@ impl Special {
 
             #[cfg(feature = "colored")]
             Self::FgColor(color, format) => {
-                let s = format.to_str(record, colorize)?;
+                let s = format.to_str(ctx)?;
 
                 if colorize {
                     write!(writer, "{}", s.color(*color))
@@ -442,7 +667,7 @@ impl Special {
             }
             #[cfg(feature = "colored")]
             Self::FgColorBranch(pallet, format) => {
-                let s = format.to_str(record, colorize)?;
+                let s = format.to_str(ctx)?;
 
                 if colorize {
                     write!(writer, "{}", s.color(pallet.select(record.level())))
@@ -452,7 +677,7 @@ impl Special {
             }
             #[cfg(feature = "colored")]
             Self::BgColor(color, format) => {
-                let s = format.to_str(record, colorize)?;
+                let s = format.to_str(ctx)?;
 
                 if colorize {
                     write!(writer, "{}", s.on_color(*color))
@@ -462,7 +687,7 @@ impl Special {
             }
             #[cfg(feature = "colored")]
             Self::BgColorBranch(pallet, format) => {
-                let s = format.to_str(record, colorize)?;
+                let s = format.to_str(ctx)?;
 
                 if colorize {
                     write!(writer, "{}", s.on_color(pallet.select(record.level())))
@@ -472,7 +697,7 @@ impl Special {
             }
             #[cfg(feature = "colored")]
             Self::Bold(format) => {
-                let s = format.to_str(record, colorize)?;
+                let s = format.to_str(ctx)?;
 
                 if colorize {
                     write!(writer, "{}", s.bold())
@@ -482,7 +707,7 @@ impl Special {
             }
             #[cfg(feature = "colored")]
             Self::Dimmed(format) => {
-                let s = format.to_str(record, colorize)?;
+                let s = format.to_str(ctx)?;
 
                 if colorize {
                     write!(writer, "{}", s.dimmed())
@@ -492,7 +717,7 @@ impl Special {
             }
             #[cfg(feature = "colored")]
             Self::Italic(format) => {
-                let s = format.to_str(record, colorize)?;
+                let s = format.to_str(ctx)?;
 
                 if colorize {
                     write!(writer, "{}", s.italic())
@@ -502,7 +727,7 @@ impl Special {
             }
             #[cfg(feature = "colored")]
             Self::Reversed(format) => {
-                let s = format.to_str(record, colorize)?;
+                let s = format.to_str(ctx)?;
 
                 if colorize {
                     write!(writer, "{}", s.reversed())
@@ -512,7 +737,7 @@ impl Special {
             }
             #[cfg(feature = "colored")]
             Self::Underline(format) => {
-                let s = format.to_str(record, colorize)?;
+                let s = format.to_str(ctx)?;
 
                 if colorize {
                     write!(writer, "{}", s.underline())
@@ -522,7 +747,7 @@ impl Special {
             }
             #[cfg(feature = "colored")]
             Self::StrikeThrough(format) => {
-                let s = format.to_str(record, colorize)?;
+                let s = format.to_str(ctx)?;
 
                 if colorize {
                     write!(writer, "{}", s.strikethrough())
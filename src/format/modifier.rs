@@ -0,0 +1,135 @@
+use super::{FormatError, Source};
+use unicode_width::UnicodeWidthChar;
+
+// The largest width and precision. (Larger ones are mistakes.)
+const MAX: usize = 4096;
+
+/// The alignment of the text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// The width and the precision of a specifier. (e.g. `%-5L`, `%.30M`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Modifier {
+    align: Align,
+    width: Option<usize>,
+    precision: Option<usize>,
+}
+
+impl Modifier {
+    /// Parse a modifier. Returns "None" when there is no modifier.
+    pub fn parse(s: &mut Source) -> Result<Option<Self>, FormatError> {
+        let align = match s.peek() {
+            Some('-') | Some('<') => Some(Align::Left),
+            Some('>') => Some(Align::Right),
+            Some('^') => Some(Align::Center),
+            _ => None,
+        };
+
+        if align.is_some() {
+            s.next();
+        }
+
+        let width = parse_number(s)?;
+
+        let precision = if s.peek() == Some('.') {
+            s.next();
+            match parse_number(s)? {
+                Some(p) => Some(p),
+                None => {
+                    return Err(match s.next() {
+                        Some(c) => s.unexpected_char(None, c, "a precision"),
+                        None => s.unexpected_end(None, "a precision"),
+                    })
+                }
+            }
+        } else {
+            None
+        };
+
+        if align.is_none() && width.is_none() && precision.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            // Align to the right by default like "printf".
+            align: align.unwrap_or(Align::Right),
+            width,
+            precision,
+        }))
+    }
+
+    /// Truncate and pad the text.
+    pub fn apply(&self, s: &str) -> String {
+        let (mut res, width) = truncate(s, self.precision.unwrap_or(usize::MAX));
+
+        let pad = self.width.unwrap_or(0).saturating_sub(width);
+        let (left, right) = match self.align {
+            Align::Left => (0, pad),
+            Align::Right => (pad, 0),
+            Align::Center => (pad / 2, pad - pad / 2),
+        };
+
+        res.insert_str(0, &" ".repeat(left));
+        res.push_str(&" ".repeat(right));
+        res
+    }
+}
+
+fn parse_number(s: &mut Source) -> Result<Option<usize>, FormatError> {
+    let position = s.position();
+    let mut digits = String::new();
+
+    while let Some(d) = s.peek().filter(char::is_ascii_digit) {
+        s.next();
+        digits.push(d);
+    }
+
+    if digits.is_empty() {
+        return Ok(None);
+    }
+
+    match digits.parse() {
+        Ok(n) if n <= MAX => Ok(Some(n)),
+        _ => Err(s.too_large(position, digits, MAX)),
+    }
+}
+
+// Truncate the text to the display width, and return it with its width.
+// ANSI escape sequences are kept and not counted.
+fn truncate(s: &str, max: usize) -> (String, usize) {
+    let mut res = String::with_capacity(s.len());
+    let mut width = 0;
+    let mut full = false;
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Copy the whole escape sequence. (e.g. "\x1b[1;31m")
+            res.push(c);
+            for c in chars.by_ref() {
+                res.push(c);
+                if c != '[' && ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+            continue;
+        }
+
+        let w = c.width().unwrap_or(0);
+        if full || width + w > max {
+            // Keep only escape sequences after the text is cut. (e.g. "\x1b[0m")
+            full = true;
+            continue;
+        }
+
+        res.push(c);
+        width += w;
+    }
+
+    (res, width)
+}
@@ -0,0 +1,54 @@
+use std::sync::OnceLock;
+
+/// The id of the current process.
+pub fn pid() -> u32 {
+    std::process::id()
+}
+
+/// The host name. (Resolved once.)
+pub fn hostname() -> &'static str {
+    static HOSTNAME: OnceLock<String> = OnceLock::new();
+
+    HOSTNAME.get_or_init(|| {
+        resolve_hostname()
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .or_else(|| std::env::var("COMPUTERNAME").ok())
+            .unwrap_or_else(|| String::from("localhost"))
+    })
+}
+
+#[cfg(unix)]
+fn resolve_hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+
+    // SAFETY: The buffer is valid for "buf.len()" bytes.
+    if unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) } != 0 {
+        return None;
+    }
+
+    // The name may be not null-terminated when it is truncated.
+    let len = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+    match String::from_utf8_lossy(&buf[..len]).into_owned() {
+        s if s.is_empty() => None,
+        s => Some(s),
+    }
+}
+
+#[cfg(not(unix))]
+fn resolve_hostname() -> Option<String> {
+    None
+}
+
+/// The program name. (The file name of `argv[0]`.)
+pub fn program() -> &'static str {
+    static PROGRAM: OnceLock<String> = OnceLock::new();
+
+    PROGRAM.get_or_init(|| {
+        std::env::args_os()
+            .next()
+            .map(std::path::PathBuf::from)
+            .or_else(|| std::env::current_exe().ok())
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| String::from("<unknown>"))
+    })
+}
@@ -0,0 +1,129 @@
+use super::syslog::{self, Facility};
+use super::{escape, process, thread};
+use log::Record;
+use std::io;
+
+#[cfg(feature = "kv")]
+use super::kv;
+
+/// A built-in renderer of whole records. (e.g. `%R(json)`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Renderer {
+    /// JSON Lines
+    Json,
+    /// logfmt
+    Logfmt,
+    /// Syslog (RFC 5424)
+    Rfc5424(Facility),
+    /// Syslog (RFC 3164, BSD)
+    Rfc3164(Facility),
+}
+
+impl std::str::FromStr for Renderer {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        // "<name>" or "<name>,<argument>"
+        let mut args = s.splitn(2, ',');
+        let name = args.next().unwrap_or_default().trim();
+        let arg = args.next().map(str::trim);
+
+        let facility = || match arg {
+            Some(f) => f.parse(),
+            None => Ok(Facility::default()),
+        };
+
+        match (name, arg) {
+            ("json" | "JSON", None) => Ok(Self::Json),
+            ("logfmt", None) => Ok(Self::Logfmt),
+            ("rfc5424" | "RFC5424", _) => Ok(Self::Rfc5424(facility()?)),
+            ("rfc3164" | "RFC3164", _) => Ok(Self::Rfc3164(facility()?)),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Renderer {
+    pub fn write<W: io::Write>(&self, writer: &mut W, record: &Record) -> io::Result<()> {
+        match self {
+            Self::Json => json(writer, record),
+            Self::Logfmt => logfmt(writer, record),
+            Self::Rfc5424(facility) => syslog::rfc5424(writer, record, *facility),
+            Self::Rfc3164(facility) => syslog::rfc3164(writer, record, *facility),
+        }
+    }
+}
+
+// Quote the string as a JSON string, or "null".
+fn json_str(s: Option<&str>) -> String {
+    match s {
+        Some(s) => format!("\"{}\"", escape::json(s)),
+        None => String::from("null"),
+    }
+}
+
+fn json<W: io::Write>(writer: &mut W, record: &Record) -> io::Result<()> {
+    write!(writer, "{{")?;
+
+    #[cfg(feature = "chrono")]
+    write!(
+        writer,
+        "\"timestamp\":\"{}\",",
+        chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+    )?;
+
+    write!(
+        writer,
+        "\"level\":\"{}\",\"target\":{},\"module\":{},\"file\":{},\"line\":{},",
+        record.level(),
+        json_str(Some(record.target())),
+        json_str(record.module_path()),
+        json_str(record.file()),
+        record
+            .line()
+            .map_or_else(|| String::from("null"), |l| l.to_string()),
+    )?;
+
+    write!(
+        writer,
+        "\"thread\":{},\"thread_id\":{},\"pid\":{},\"message\":{}",
+        json_str(Some(&thread::name())),
+        thread::id(),
+        process::pid(),
+        json_str(Some(&record.args().to_string())),
+    )?;
+
+    #[cfg(feature = "kv")]
+    {
+        let pairs: Vec<_> = kv::pairs(record)
+            .iter()
+            .map(|(k, v)| format!("{}:{}", json_str(Some(k)), json_str(Some(v))))
+            .collect();
+        write!(writer, ",\"fields\":{{{}}}", pairs.join(","))?;
+    }
+
+    write!(writer, "}}")
+}
+
+fn logfmt<W: io::Write>(writer: &mut W, record: &Record) -> io::Result<()> {
+    #[cfg(feature = "chrono")]
+    write!(
+        writer,
+        "ts={} ",
+        chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+    )?;
+
+    write!(
+        writer,
+        "level={} target={} msg={}",
+        record.level().to_string().to_lowercase(),
+        escape::logfmt(record.target()),
+        escape::logfmt(&record.args().to_string()),
+    )?;
+
+    #[cfg(feature = "kv")]
+    for (k, v) in kv::pairs(record) {
+        write!(writer, " {}={}", escape::logfmt_key(&k), escape::logfmt(&v))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use super::FormatError;
+use std::str::CharIndices;
+
+/// The format string with the current position.
+pub struct Source<'a> {
+    format: &'a str,
+    chars: CharIndices<'a>,
+    // The position of the last character.
+    last: usize,
+    // The position of the next character.
+    position: usize,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(format: &'a str) -> Self {
+        Self {
+            format,
+            chars: format.char_indices(),
+            last: 0,
+            position: 0,
+        }
+    }
+
+    pub fn next(&mut self) -> Option<char> {
+        let (i, c) = self.chars.next()?;
+        self.last = i;
+        self.position = i + c.len_utf8();
+        Some(c)
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.chars.clone().next().map(|c| c.1)
+    }
+
+    /// The position of the next character.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Consume the character "ch".
+    pub fn expect(
+        &mut self,
+        ch: char,
+        specifier: char,
+        expected: &'static str,
+    ) -> Result<(), FormatError> {
+        match self.next() {
+            Some(c) if c == ch => Ok(()),
+            Some(c) => Err(self.unexpected_char(Some(specifier), c, expected)),
+            None => Err(self.unexpected_end(Some(specifier), expected)),
+        }
+    }
+
+    /// Consume characters until "ch".
+    pub fn take_until(
+        &mut self,
+        ch: char,
+        specifier: char,
+        expected: &'static str,
+    ) -> Result<String, FormatError> {
+        let mut res = String::new();
+
+        loop {
+            match self.next() {
+                Some(c) if c == ch => break Ok(res),
+                Some(c) => res.push(c),
+                None => break Err(self.unexpected_end(Some(specifier), expected)),
+            }
+        }
+    }
+
+    pub fn unexpected_end(&self, specifier: Option<char>, expected: &'static str) -> FormatError {
+        FormatError::UnexpectedEnd {
+            format: self.format.to_string(),
+            position: self.format.len(),
+            specifier,
+            expected,
+        }
+    }
+
+    pub fn unexpected_char(
+        &self,
+        specifier: Option<char>,
+        found: char,
+        expected: &'static str,
+    ) -> FormatError {
+        FormatError::UnexpectedChar {
+            format: self.format.to_string(),
+            position: self.last,
+            specifier,
+            found,
+            expected,
+        }
+    }
+
+    pub fn invalid_specifier(&self, specifier: char) -> FormatError {
+        FormatError::InvalidSpecifier {
+            format: self.format.to_string(),
+            position: self.last,
+            specifier,
+        }
+    }
+
+    pub fn invalid_argument(
+        &self,
+        position: usize,
+        specifier: char,
+        argument: String,
+    ) -> FormatError {
+        FormatError::InvalidArgument {
+            format: self.format.to_string(),
+            position,
+            specifier,
+            argument,
+        }
+    }
+
+    pub fn too_large(&self, position: usize, number: String, max: usize) -> FormatError {
+        FormatError::TooLarge {
+            format: self.format.to_string(),
+            position,
+            number,
+            max,
+        }
+    }
+
+    // This function is not used when feature "colored" is disabled.
+    #[allow(dead_code)]
+    pub fn invalid_color(&self, position: usize, specifier: char, color: String) -> FormatError {
+        FormatError::InvalidColor {
+            format: self.format.to_string(),
+            position,
+            specifier,
+            color,
+        }
+    }
+}
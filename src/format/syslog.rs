@@ -0,0 +1,173 @@
+use super::process;
+use log::{Level, Record};
+use std::io;
+
+#[cfg(feature = "kv")]
+use super::kv;
+
+/// The syslog facility.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Facility {
+    Kern = 0,
+    #[default]
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    AuthPriv = 10,
+    Ftp = 11,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+impl std::str::FromStr for Facility {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        Ok(match s {
+            "kern" => Self::Kern,
+            "user" => Self::User,
+            "mail" => Self::Mail,
+            "daemon" => Self::Daemon,
+            "auth" => Self::Auth,
+            "syslog" => Self::Syslog,
+            "lpr" => Self::Lpr,
+            "news" => Self::News,
+            "uucp" => Self::Uucp,
+            "cron" => Self::Cron,
+            "authpriv" => Self::AuthPriv,
+            "ftp" => Self::Ftp,
+            "local0" => Self::Local0,
+            "local1" => Self::Local1,
+            "local2" => Self::Local2,
+            "local3" => Self::Local3,
+            "local4" => Self::Local4,
+            "local5" => Self::Local5,
+            "local6" => Self::Local6,
+            "local7" => Self::Local7,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// The syslog severity of the level.
+pub fn severity(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+fn priority(facility: Facility, level: Level) -> u8 {
+    (facility as u8) * 8 + severity(level)
+}
+
+// Make the string a valid header field. (Printable ASCII without spaces.)
+fn header(s: &str, max: usize) -> String {
+    let res: String = s
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max)
+        .collect();
+
+    match res.is_empty() {
+        true => String::from("-"),
+        false => res,
+    }
+}
+
+/// RFC 5424: `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID [SD] MSG`
+pub fn rfc5424<W: io::Write>(
+    writer: &mut W,
+    record: &Record,
+    facility: Facility,
+) -> io::Result<()> {
+    #[cfg(feature = "chrono")]
+    let timestamp = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, false);
+    #[cfg(not(feature = "chrono"))]
+    let timestamp = "-";
+
+    write!(
+        writer,
+        "<{}>1 {} {} {} {} {} ",
+        priority(facility, record.level()),
+        timestamp,
+        header(process::hostname(), 255),
+        header(process::program(), 48),
+        process::pid(),
+        header(record.target(), 32),
+    )?;
+
+    write_structured_data(writer, record)?;
+    write!(writer, " {}", record.args())
+}
+
+#[cfg(feature = "kv")]
+fn write_structured_data<W: io::Write>(writer: &mut W, record: &Record) -> io::Result<()> {
+    let pairs = kv::pairs(record);
+    if pairs.is_empty() {
+        return write!(writer, "-");
+    }
+
+    // "32473" is the enterprise number reserved for documentation. (RFC 5612)
+    write!(writer, "[fields@32473")?;
+    for (k, v) in pairs {
+        // PARAM-NAME: Printable ASCII without '=', ' ', ']' and '"'.
+        let name: String = k
+            .chars()
+            .filter(|c| c.is_ascii_graphic() && !matches!(c, '=' | ']' | '"'))
+            .take(32)
+            .collect();
+        // PARAM-VALUE: '"', '\' and ']' must be escaped.
+        let value = v
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace(']', "\\]");
+        write!(writer, " {}=\"{}\"", header(&name, 32), value)?;
+    }
+    write!(writer, "]")
+}
+
+#[cfg(not(feature = "kv"))]
+fn write_structured_data<W: io::Write>(writer: &mut W, _record: &Record) -> io::Result<()> {
+    write!(writer, "-")
+}
+
+/// RFC 3164: `<PRI>Mmm dd hh:mm:ss HOSTNAME TAG[PID]: MSG`
+pub fn rfc3164<W: io::Write>(
+    writer: &mut W,
+    record: &Record,
+    facility: Facility,
+) -> io::Result<()> {
+    write!(writer, "<{}>", priority(facility, record.level()))?;
+
+    // Without the timestamp, the receiver adds the time of receipt. (RFC 3164 4.3.2)
+    #[cfg(feature = "chrono")]
+    write!(writer, "{} ", chrono::Local::now().format("%b %e %T"))?;
+
+    write!(
+        writer,
+        "{} {}[{}]: {}",
+        header(process::hostname(), 255),
+        // TAG must be alphanumeric and up to 32 characters.
+        process::program()
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            .take(32)
+            .collect::<String>(),
+        process::pid(),
+        record.args()
+    )
+}
@@ -0,0 +1,28 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+// The next thread id.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    // The id of this thread. ("0" means not assigned yet.)
+    static ID: Cell<usize> = const { Cell::new(0) };
+}
+
+/// The name of the current thread.
+pub fn name() -> String {
+    thread::current().name().unwrap_or("<unnamed>").to_string()
+}
+
+/// The numeric id of the current thread.
+///
+/// The ids are assigned sequentially from `1` when threads log for the first time.
+pub fn id() -> usize {
+    ID.with(|id| {
+        if id.get() == 0 {
+            id.set(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        }
+        id.get()
+    })
+}
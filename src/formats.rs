@@ -6,10 +6,109 @@
 //! For the details, see [the document](https://github.com/watcol/fmtlog/blob/main/formats.md).
 //!
 
+/// JSON Lines with escaped strings.
+///
+/// # Example
+///
+/// ```rust
+/// use fmtlog::{formats::JSON, Format};
+/// use log::{Level, Record};
+///
+/// let record = Record::builder()
+///     .args(format_args!("say \"hi\"\nbye"))
+///     .level(Level::Info)
+///     .target("my_app")
+///     .build();
+/// let line = Format::new(JSON).unwrap().render(&record, false);
+///
+/// assert!(line.contains(r#""level":"INFO","target":"my_app","module":null"#));
+/// assert!(line.contains(r#""message":"say \"hi\"\nbye""#));
+/// assert!(line.ends_with("}\n"));
+/// ```
+pub const JSON: &str = "%R(json)\n";
+
+/// [logfmt](https://brandur.org/logfmt) with quoted values.
+///
+/// # Example
+///
+/// ```rust
+/// use fmtlog::{formats::LOGFMT, Format};
+/// use log::{Level, Record};
+///
+/// let record = Record::builder()
+///     .args(format_args!("say \"hi\""))
+///     .level(Level::Info)
+///     .target("app")
+///     .build();
+/// let line = Format::new(LOGFMT).unwrap().render(&record, false);
+///
+/// assert!(line.ends_with("level=info target=app msg=\"say \\\"hi\\\"\"\n"));
+///
+/// // Keys are made valid. (Requires feature: `kv`)
+/// # #[cfg(feature = "kv")]
+/// # {
+/// let record = Record::builder()
+///     .args(format_args!("m"))
+///     .key_values(&[("user id", "5"), ("", "v")])
+///     .build();
+/// let line = Format::new(LOGFMT).unwrap().render(&record, false);
+///
+/// assert!(line.ends_with(" msg=m user_id=5 _=v\n"));
+/// # }
+/// ```
+pub const LOGFMT: &str = "%R(logfmt)\n";
+
+/// Syslog messages. (RFC 5424)
+///
+/// The facility is `user`. Use `%R(rfc5424,<facility>)` to change it.
+///
+/// # Example
+///
+/// ```rust
+/// use fmtlog::{formats::RFC5424, Format};
+/// use log::{Level, Record};
+///
+/// let record = Record::builder()
+///     .args(format_args!("Hello!"))
+///     .level(Level::Warn)
+///     .target("my_app")
+///     .build();
+/// let line = Format::new(RFC5424).unwrap().render(&record, false);
+///
+/// assert!(line.starts_with("<12>1 "));
+/// assert!(line.ends_with(" my_app - Hello!\n"));
+/// ```
+pub const RFC5424: &str = "%R(rfc5424)\n";
+
+/// Syslog messages. (RFC 3164, the legacy BSD format)
+///
+/// The facility is `user`. Use `%R(rfc3164,<facility>)` to change it.
 #[cfg(feature = "chrono")]
-pub const TOML: &str = "[%T(%+)]\ntarget = \"%N\"\nlevel = \"%L\"\ninfo = \"%M\"\n\n";
+pub const RFC3164: &str = "%R(rfc3164)\n";
+
+/// TOML with escaped strings.
+///
+/// # Example
+///
+/// ```rust
+/// use fmtlog::{formats::TOML, Format};
+/// use log::{Level, Record};
+///
+/// let record = Record::builder()
+///     .args(format_args!("say \"hi\"\nbye"))
+///     .level(Level::Info)
+///     .build();
+/// let doc = Format::new(TOML).unwrap().render(&record, false);
+///
+/// assert!(doc.contains("info = \"say \\\"hi\\\"\\nbye\"\n"));
+/// ```
 #[cfg(feature = "chrono")]
-pub const YAML: &str = "- date: %T(%+)\n  target: %N\n  level: %L\n  info: %M\n\n";
+pub const TOML: &str =
+    "[\"%T(%+)\"]\ntarget = \"%E(toml){%N}\"\nlevel = \"%L\"\ninfo = \"%E(toml){%M}\"\n\n";
+/// YAML with escaped strings.
+#[cfg(feature = "chrono")]
+pub const YAML: &str =
+    "- date: %T(%+)\n  target: \"%E(yaml){%N}\"\n  level: %L\n  info: \"%E(yaml){%M}\"\n\n";
 
 #[cfg(feature = "colored")]
 pub use self::colored::*;
@@ -40,6 +139,9 @@ mod normal {
     pub const DEBUG2: &str = "[%L] %M (at %S in %N)\n";
     pub const DEBUG2_LOWER: &str = "[%l] %M (at %S in %N)\n";
 
+    pub const DEBUG_THREAD: &str = "[%N (%S) %t#%I] %L: %M\n";
+    pub const DEBUG_THREAD_LOWER: &str = "[%N (%S) %t#%I] %l: %M\n";
+
     pub const PRETTY_ENV_LOGGER: &str = " %L %N > %M\n";
     pub const FLEXI_LOGGER: &str = "%L [%m] %M\n";
     pub const STDERRLOG: &str = "%L - %M\n";
@@ -54,6 +156,8 @@ mod normal {
     pub const SIMPLELOG: &str = "%T(%T) [%L] %M\n";
     #[cfg(feature = "chrono")]
     pub const STDERRLOG2: &str = "%T(%Y-%m-%dT%T%:z) - %L - %M\n";
+    #[cfg(feature = "chrono")]
+    pub const SYSLOG: &str = "%T(%b %e %T) %H %p[%P]: %L: %M\n";
 }
 
 #[allow(dead_code)]
@@ -83,6 +187,11 @@ mod colored {
     pub const DEBUG2: &str = "[%F(red,yellow,green,purple,blue){%b{%L}}] %M (at %S in %N)\n";
     pub const DEBUG2_LOWER: &str = "[%F(red,yellow,green,purple,blue){%b{%l}}] %M (at %S in %N)\n";
 
+    pub const DEBUG_THREAD: &str =
+        "[%N (%S) %F(cyan){%t#%I}] %F(red,yellow,green,purple,blue){%b{%L}}: %M\n";
+    pub const DEBUG_THREAD_LOWER: &str =
+        "[%N (%S) %F(cyan){%t#%I}] %F(red,yellow,green,purple,blue){%b{%l}}: %M\n";
+
     pub const PRETTY_ENV_LOGGER: &str = " %F(red,yellow,green,blue,purple){%L} %b{%N} > %M\n";
     pub const FLEXI_LOGGER: &str =
         "%F(red,yellow,white,white,black){%b{%L}} [%m] %F(red,yellow,white,white,black){%b{%M}}\n";
@@ -100,4 +209,6 @@ mod colored {
     pub const SIMPLELOG: &str = "%T(%T) [%L] %M\n";
     #[cfg(feature = "chrono")]
     pub const STDERRLOG2: &str = "%F(red,purple,yellow,cyan,blue){%T(%Y-%m-%dT%T%:z) - %L - %M}\n";
+    #[cfg(feature = "chrono")]
+    pub const SYSLOG: &str = "%T(%b %e %T) %H %p[%P]: %F(red,yellow,green,purple,blue){%L}: %M\n";
 }
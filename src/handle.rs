@@ -0,0 +1,140 @@
+use crate::module::Filter;
+use crate::stream::Streams;
+use log::{set_max_level, LevelFilter};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// A handle to change the settings of an active logger.
+///
+/// This handle is returned by [`Logger::set`](struct.Logger.html#method.set),
+/// and can be cloned and shared between threads.
+#[derive(Clone, Debug)]
+pub struct LoggerHandle {
+    filter: Arc<RwLock<Filter>>,
+    streams: Arc<Streams>,
+}
+
+impl LoggerHandle {
+    pub(crate) fn new(filter: Arc<RwLock<Filter>>, streams: Arc<Streams>) -> Self {
+        Self { filter, streams }
+    }
+
+    /// Get the current log level.
+    pub fn level(&self) -> LevelFilter {
+        self.filter
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .default_level()
+    }
+
+    /// Change the log level.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// #[macro_use]
+    /// extern crate log;
+    ///
+    /// use fmtlog::LevelFilter;
+    ///
+    /// fn main() {
+    ///     let handle = fmtlog::default().set().unwrap();
+    ///     debug!("Hidden.");
+    ///
+    ///     handle.set_level(LevelFilter::Debug);
+    ///     debug!("Shown!"); // DEBUG: Shown!
+    /// }
+    /// ```
+    pub fn set_level<T: Into<LevelFilter>>(&self, level: T) {
+        self.update(|f| f.set_level(level.into()));
+    }
+
+    /// Change the log level of a module.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::LevelFilter;
+    ///
+    /// let handle = fmtlog::default().set().unwrap();
+    /// handle.set_module_level("my_app::db", LevelFilter::Trace);
+    ///
+    /// assert_eq!(log::max_level(), LevelFilter::Trace);
+    /// ```
+    pub fn set_module_level<T: Into<String>, L: Into<LevelFilter>>(&self, module: T, level: L) {
+        self.update(|f| f.set_directive(module.into(), level.into()));
+    }
+
+    /// Remove the log level of a module set by
+    /// [`Config::module_level`](struct.Config.html#method.module_level) or
+    /// [`LoggerHandle::set_module_level`](#method.set_module_level).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, LevelFilter};
+    ///
+    /// let handle = fmtlog::new(Config::new().module_level("hyper", LevelFilter::Trace))
+    ///     .set()
+    ///     .unwrap();
+    /// handle.remove_module_level("hyper");
+    ///
+    /// assert_eq!(log::max_level(), LevelFilter::Info);
+    /// ```
+    pub fn remove_module_level<T: AsRef<str>>(&self, module: T) {
+        self.update(|f| f.remove_directive(module.as_ref()));
+    }
+
+    /// Close and reopen all file outputs.
+    ///
+    /// Call this after an external tool like logrotate moved the files.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::Config;
+    ///
+    /// let path = std::env::temp_dir().join(format!("fmtlog-doc-{}.log", std::process::id()));
+    /// let moved = path.with_extension("log.1");
+    ///
+    /// let handle = fmtlog::new(Config::new().format("%M\n").output(path.clone()))
+    ///     .set()
+    ///     .unwrap();
+    /// log::info!("Old");
+    ///
+    /// std::fs::rename(&path, &moved).unwrap();
+    /// handle.reopen();
+    /// log::info!("New");
+    ///
+    /// assert_eq!(std::fs::read_to_string(&moved).unwrap(), "Old\n");
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "New\n");
+    /// # std::fs::remove_file(path).unwrap();
+    /// # std::fs::remove_file(moved).unwrap();
+    /// ```
+    pub fn reopen(&self) {
+        self.streams.reopen();
+    }
+
+    /// [**unix**] Reopen all file outputs when the process receives `SIGHUP`.
+    ///
+    /// A background thread calls [`LoggerHandle::reopen`](#method.reopen)
+    /// for each signal. The handler can be installed only once in a process.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// let handle = fmtlog::default().set().unwrap();
+    /// handle.reopen_on_sighup().unwrap();
+    /// ```
+    #[cfg(unix)]
+    pub fn reopen_on_sighup(&self) -> std::io::Result<()> {
+        let handle = self.clone();
+        crate::signal::on_sighup(move || handle.reopen())
+    }
+
+    // Update the filter and the max level of the "log" crate.
+    fn update<F: FnOnce(&mut Filter)>(&self, f: F) {
+        let mut filter = self.filter.write().unwrap_or_else(PoisonError::into_inner);
+        f(&mut filter);
+        set_max_level(filter.max_level());
+    }
+}
@@ -0,0 +1,82 @@
+//! The native protocol of systemd-journald.
+use crate::format::{process, syslog};
+use log::Record;
+use std::io::{self, Write};
+use std::os::unix::net::UnixDatagram;
+
+/// The socket of journald.
+pub const SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Send the record as one datagram.
+pub fn send(socket: &UnixDatagram, record: &Record) -> io::Result<()> {
+    let mut buf = Vec::new();
+
+    field(&mut buf, "MESSAGE", &record.args().to_string())?;
+    field(
+        &mut buf,
+        "PRIORITY",
+        &syslog::severity(record.level()).to_string(),
+    )?;
+    field(&mut buf, "SYSLOG_IDENTIFIER", process::program())?;
+    field(&mut buf, "TARGET", record.target())?;
+
+    if let Some(file) = record.file() {
+        field(&mut buf, "CODE_FILE", file)?;
+    }
+    if let Some(line) = record.line() {
+        field(&mut buf, "CODE_LINE", &line.to_string())?;
+    }
+    if let Some(module) = record.module_path() {
+        field(&mut buf, "CODE_MODULE", module)?;
+    }
+
+    #[cfg(feature = "kv")]
+    for (k, v) in crate::format::kv::pairs(record) {
+        field(&mut buf, &name(&k), &v)?;
+    }
+
+    // Large logs must be sent with a memfd, which is not supported.
+    // (Fails with "EMSGSIZE".)
+    socket.send(&buf).map(|_| ())
+}
+
+// Write a field. ("KEY=value\n", or the binary form for multi-line values.)
+fn field<W: Write>(writer: &mut W, name: &str, value: &str) -> io::Result<()> {
+    if value.contains('\n') {
+        writeln!(writer, "{}", name)?;
+        writer.write_all(&(value.len() as u64).to_le_bytes())?;
+        writer.write_all(value.as_bytes())?;
+        writeln!(writer)
+    } else {
+        writeln!(writer, "{}={}", name, value)
+    }
+}
+
+// The fields written by fmtlog.
+#[cfg(feature = "kv")]
+const RESERVED: &[&str] = &["MESSAGE", "PRIORITY", "SYSLOG_IDENTIFIER", "TARGET"];
+
+// Make the key a valid field name. (Uppercase letters, digits and '_',
+// not starting with '_' or a digit.)
+// Names used by fmtlog are prefixed with "FIELD_". (e.g. "priority" -> "FIELD_PRIORITY")
+#[cfg(feature = "kv")]
+fn name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() => c.to_ascii_uppercase(),
+            _ => '_',
+        })
+        .skip_while(|c| *c == '_' || c.is_ascii_digit())
+        .collect();
+
+    let name = if name.is_empty() {
+        String::from("FIELD")
+    } else if RESERVED.contains(&name.as_str()) || name.starts_with("CODE_") {
+        format!("FIELD_{}", name)
+    } else {
+        name
+    };
+
+    name.chars().take(64).collect()
+}
@@ -52,6 +52,23 @@
 //! | `%M` | `An error has occured.` | The log message. |
 //! | `%l` | `info` | The log level. (lowercase) |
 //! | `%L` | `INFO` | The log level. (uppercase) |
+//! | `%t` | `main` | The name of the current thread. (`<unnamed>` if the thread has no name.) |
+//! | `%I` | `1` | The numeric id of the current thread. (Assigned sequentially by fmtlog.) |
+//! | `%P` | `1234` | The process id. |
+//! | `%H` | `localhost` | The host name. |
+//! | `%p` | `my_app` | The program name. (The file name of `argv[0]`.) |
+//! | `%R(json)` | `{"level":"INFO",...}` | The whole log as a JSON object. (See [`formats::JSON`](formats/constant.JSON.html).) |
+//! | `%R(logfmt)` | `level=info msg=Hello` | The whole log as logfmt. (See [`formats::LOGFMT`](formats/constant.LOGFMT.html).) |
+//! | `%R(rfc5424)` | `<14>1 2021-01-01T12:00:00.000000+09:00 localhost my_app 1234 my_app - Hello` | The whole log as a syslog message. (RFC 5424) |
+//! | `%R(rfc3164)` | `<14>Jan  1 12:00:00 localhost my_app[1234]: Hello` | The whole log as a syslog message. (RFC 3164, The timestamp requires feature `chrono`.) |
+//! | `%R(<rfc5424 or rfc3164>,<facility>)` | `%R(rfc5424,local0)` | The syslog message with the facility. (`kern`, `user`, `mail`, `daemon`, `auth`, `syslog`, `lpr`, `news`, `uucp`, `cron`, `authpriv`, `ftp` and `local0`-`local7`) |
+//! | `%E(<syntax>){...}` | `%E(json){%M}` -> `say \"hi\"` | Escape the text for the syntax. (`json`, `toml` and `yaml` escape the content of double-quoted strings, and `csv` and `logfmt` quote the value if needed.) |
+//! | `%K` | `user_id=5 ip=::1` | All key-values of the log. **Requires feature: `kv`** |
+//! | `%K(<key>)` | `%K(user_id)` -> `5` | The value of the key. **Requires feature: `kv`** |
+//! | `%K{...}` | `%K{ %k: %v}` -> ` user_id: 5 ip: ::1` | Format each key-value by the body. **Requires feature: `kv`** |
+//! | `%K(<key>){...}` | `%K(user_id){ (user %v)}` -> ` (user 5)` | Format the key-value by the body if it exists. **Requires feature: `kv`** |
+//! | `%k` | `user_id` | The key. (use in `%K{...}`.) **Requires feature: `kv`** |
+//! | `%v` | `5` | The value. (use in `%K{...}`.) **Requires feature: `kv`** |
 //! | `%T(<format>)` | `%T(%D %T)` -> `01/01/21 12:00:00` | The local time. (see [chrono's format specification](https://docs.rs/chrono/0.4/chrono/format/strftime)). **Requires feature: `chrono`** |
 //! | `%U(<format>)` | `%T(%D %T)` -> `01/01/21 12:00:00` | The UTC time. (see [chrono's format specification](https://docs.rs/chrono/0.4/chrono/format/strftime)). **Requires feature: `chrono`** |
 //! | `%F(<color>){...}` | | Set the foreground color. **Requires feature: `colored`** |
@@ -65,6 +82,67 @@
 //! | `%u{...}` | | Underline the text. **Requires feature: `colored`** |
 //! | `%s{...}` | | Strikethrough the text. **Requires feature: `colored`** |
 //!
+//! ### Key-Values (Requires feature: `kv`)
+//! Structured key-values passed like `info!(user_id = 5; "login")` can be formatted by `%K`.
+//!
+//! ```rust
+//! # #[cfg(feature = "kv")]
+//! # {
+//! use fmtlog::Format;
+//! use log::{Level, Record};
+//!
+//! let format = Format::new("%M%K{ %k=%v}%K(user_id){ (user %v)}").unwrap();
+//! let record = Record::builder()
+//!     .args(format_args!("login"))
+//!     .level(Level::Info)
+//!     .key_values(&[("user_id", 5)])
+//!     .build();
+//!
+//! assert_eq!(format.render(&record, false), "login user_id=5 (user 5)");
+//! # }
+//! ```
+//!
+//! ### Width and Precision
+//! Specifiers can be aligned and truncated like `printf`. (e.g. `%-5L`, `%.30M`)
+//! The width is measured in the display width, so colored and wide characters are aligned.
+//!
+//! | Modifier | Example | Description |
+//! |----------|---------|-------------|
+//! | `<width>` | `%8N` | Pad to the width. (Align to the right.) |
+//! | `-<width>` or `<<width>` | `%-5L` | Pad to the width. (Align to the left.) |
+//! | `><width>` | `%>20N` | Pad to the width. (Align to the right.) |
+//! | `^<width>` | `%^8l` | Pad to the width. (Align to the center.) |
+//! | `.<precision>` | `%.30M` | Truncate to the width. |
+//!
+//! The width and the precision are up to 4096.
+//!
+//! ```rust
+//! use fmtlog::Format;
+//! use log::{Level, Record};
+//!
+//! let format = Format::new("[%-5L] %^7N|%.5M").unwrap();
+//! let record = Record::builder()
+//!     .args(format_args!("Hello, world!"))
+//!     .level(Level::Info)
+//!     .target("app")
+//!     .build();
+//!
+//! assert_eq!(format.render(&record, false), "[INFO ]   app  |Hello");
+//!
+//! // "中" is two columns wide, so it is cut with the following text.
+//! let format = Format::new("%.3M|%4M|").unwrap();
+//! let record = Record::builder().args(format_args!("ab中c")).build();
+//!
+//! assert_eq!(format.render(&record, false), "ab|ab中c|");
+//!
+//! let e = Format::new("%99999999999999999999M").unwrap_err();
+//! assert_eq!(e.position(), 1);
+//! assert_eq!(
+//!     e.to_string(),
+//!     "Too large number 99999999999999999999, expected up to 4096.\n  %99999999999999999999M\n   ^"
+//! );
+//! ```
+//!
 //! ### Supported Color (Requires feature: `colored`)
 //! All supported color used by `%C` and `%O` is here.
 //! - `black`
@@ -86,7 +164,6 @@
 //! - `#ffffff` (Hexadecimal RGB)
 //!
 extern crate log;
-extern crate thread_local;
 
 #[cfg(feature = "colored")]
 extern crate colored;
@@ -94,59 +171,183 @@ extern crate colored;
 pub mod formats;
 
 mod config;
+mod error;
 mod format;
+mod handle;
+#[cfg(unix)]
+mod journald;
 mod module;
+mod network;
+mod rotate;
+#[cfg(unix)]
+mod signal;
 mod stream;
 
 pub use config::*;
+pub use error::Error;
+pub use format::{Format, FormatError};
+pub use handle::LoggerHandle;
 
-use format::Format;
-use module::Modules;
-use stream::Stream;
+use module::Filter;
+use stream::{SharedStream, Stream, Streams};
 
 use log::{set_boxed_logger, set_max_level, Log, Metadata, Record, SetLoggerError};
 use std::cell::RefCell;
-use thread_local::ThreadLocal;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard};
 
 /// The body of fmtlog.
+///
+/// Logs can be written from anywhere, including destructors of thread-local values.
+///
+/// # Example
+///
+/// ```rust
+/// use fmtlog::Config;
+///
+/// struct Guard;
+///
+/// impl Drop for Guard {
+///     fn drop(&mut self) {
+///         log::info!("Thread exited.");
+///     }
+/// }
+///
+/// thread_local! {
+///     static GUARD: Guard = Guard;
+/// }
+///
+/// let path = std::env::temp_dir().join(format!("fmtlog-doc-tls-{}.log", std::process::id()));
+/// fmtlog::new(Config::new().format("%M\n").output(path.clone()))
+///     .set()
+///     .unwrap();
+///
+/// std::thread::spawn(|| {
+///     GUARD.with(|_| {});
+///     log::info!("Thread started.");
+/// })
+/// .join()
+/// .unwrap();
+///
+/// assert_eq!(
+///     std::fs::read_to_string(&path).unwrap(),
+///     "Thread started.\nThread exited.\n"
+/// );
+/// # std::fs::remove_file(path).unwrap();
+/// ```
 pub struct Logger {
     format: Format,
-    level: log::LevelFilter,
-    modules: Modules,
-    // (Output, Colorize)
-    streams: Vec<(Output, bool)>,
-    // (Stream, Colorize)
-    writer: ThreadLocal<RefCell<Vec<(Stream, bool)>>>,
+    filter: Arc<RwLock<Filter>>,
+    // Streams shared by all threads.
+    streams: Arc<Streams>,
+    on_error: ErrorPolicy,
+    // Whether an error has been reported.
+    reported: AtomicBool,
+}
+
+thread_local! {
+    // Reused to render records on each thread.
+    static BUFFER: RefCell<Buffer> = RefCell::new(Buffer::default());
+}
+
+// The capacity kept after a large record.
+const BUFFER_CAPACITY: usize = 64 * 1024;
+
+/// The record rendered once for each colorize setting.
+#[derive(Default)]
+struct Buffer {
+    // [Plain, Colorized]
+    bufs: [Vec<u8>; 2],
+    rendered: [bool; 2],
+}
+
+impl Buffer {
+    fn render(&mut self, format: &Format, record: &Record, colorize: bool) -> io::Result<&[u8]> {
+        let i = colorize as usize;
+        if !self.rendered[i] {
+            self.bufs[i].clear();
+            format.write_to(&mut self.bufs[i], record, colorize)?;
+            self.rendered[i] = true;
+        }
+
+        Ok(&self.bufs[i])
+    }
+
+    fn clear(&mut self) {
+        self.rendered = [false; 2];
+        for buf in self.bufs.iter_mut() {
+            buf.clear();
+            buf.shrink_to(BUFFER_CAPACITY);
+        }
+    }
 }
 
 impl Logger {
     /// Create a new instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the format is invalid or an output can't be opened.
+    /// Use [`Logger::try_new`](#method.try_new) to handle these errors.
     pub fn new(config: Config) -> Logger {
-        let outputs = config.output;
+        Self::try_new(config).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new instance, or return an error when the format is invalid
+    /// or an output can't be opened.
+    ///
+    /// Each output is opened once and shared by all threads.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fmtlog::{Config, Error, Logger};
+    ///
+    /// assert!(Logger::try_new(Config::new()).is_ok());
+    /// assert!(matches!(
+    ///     Logger::try_new(Config::new().format("%Q")),
+    ///     Err(Error::Format(_))
+    /// ));
+    /// ```
+    pub fn try_new(config: Config) -> Result<Logger, Error> {
+        let filter = Filter::new(&config);
+        let format = Format::new(&config.format).map_err(Error::Format)?;
+        format.init();
 
         #[cfg(feature = "colored")]
-        let streams = {
-            let colorize = config.colorize;
-            outputs
-                .into_iter()
-                .map(|o| (o.clone(), colorize.colorize(&o)))
-                .collect()
+        let colorize = |o: &Output| config.colorize.colorize(o);
+        #[cfg(not(feature = "colored"))]
+        let colorize = |_: &Output| false;
+
+        let outputs = config
+            .output
+            .iter()
+            .map(|o| {
+                SharedStream::open(o.clone(), colorize(o)).map_err(|e| Error::Open(o.clone(), e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The fallback output is opened on the first error.
+        let fallback = match &config.on_error {
+            ErrorPolicy::Fallback(o) => Some(SharedStream::new(o.clone(), colorize(o))),
+            _ => None,
         };
 
-        #[cfg(not(feature = "colored"))]
-        let streams = outputs.into_iter().map(|o| (o, false)).collect();
-
-        Logger {
-            format: Format::new(config.format).expect("Invalid Format."),
-            level: config.level,
-            modules: Modules::from(config.modules),
-            streams,
-            writer: ThreadLocal::new(),
-        }
+        Ok(Logger {
+            format,
+            filter: Arc::new(RwLock::new(filter)),
+            streams: Arc::new(Streams { outputs, fallback }),
+            on_error: config.on_error,
+            reported: AtomicBool::new(false),
+        })
     }
 
     /// Set this logger active.
     ///
+    /// The returned handle can change the log levels at runtime.
+    /// (See [`LoggerHandle`](struct.LoggerHandle.html).)
+    ///
     /// # Example
     ///
     /// ```rust
@@ -160,60 +361,137 @@ impl Logger {
     ///     info!("Hello!") // INFO: Hello!
     /// }
     /// ```
-    pub fn set(self) -> Result<(), SetLoggerError> {
-        set_max_level(self.level);
-        set_boxed_logger(Box::new(self))
+    pub fn set(self) -> Result<LoggerHandle, SetLoggerError> {
+        let handle = LoggerHandle::new(self.filter.clone(), self.streams.clone());
+        set_max_level(self.filter().max_level());
+        set_boxed_logger(Box::new(self))?;
+        Ok(handle)
+    }
+
+    fn filter(&self) -> RwLockReadGuard<'_, Filter> {
+        self.filter.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Write the record to the stream as one buffer, opening it if needed.
+    fn write(&self, stream: &SharedStream, record: &Record, buf: &mut Buffer) -> io::Result<()> {
+        // Render the record before locking.
+        let buf = match stream.is_structured() {
+            true => &[],
+            false => buf.render(&self.format, record, stream.colorize())?,
+        };
+
+        let mut guard = stream.lock();
+        let res = match &mut *guard {
+            Some(s) => Self::write_buf(s, buf, record),
+            None => stream
+                .output()
+                .to_stream()
+                .and_then(|s| Self::write_buf(guard.insert(s), buf, record)),
+        };
+
+        if res.is_err() && !guard.as_ref().is_some_and(Stream::recovers) {
+            // Reopen the stream next time.
+            *guard = None;
+        }
+
+        res
+    }
+
+    #[allow(unused_variables)]
+    fn write_buf(stream: &mut Stream, buf: &[u8], record: &Record) -> io::Result<()> {
+        #[cfg(unix)]
+        if let Stream::Journald(socket) = stream {
+            return journald::send(socket, record);
+        }
+
+        stream.write_all(buf)
+    }
+
+    // Write the record to all streams.
+    fn write_all(&self, record: &Record, buf: &mut Buffer) {
+        for stream in self.streams.outputs.iter() {
+            if let Err(e) = self.write(stream, record, buf) {
+                self.handle_error(stream.output(), e, Some(record), buf);
+            }
+        }
+    }
+
+    fn handle_error(
+        &self,
+        output: &Output,
+        error: io::Error,
+        record: Option<&Record>,
+        buf: &mut Buffer,
+    ) {
+        match &self.on_error {
+            ErrorPolicy::Ignore => {}
+            ErrorPolicy::Report => {
+                if !self.reported.swap(true, Ordering::Relaxed) {
+                    // Nothing can be done when the standard error is also broken.
+                    let _ = writeln!(
+                        io::stderr(),
+                        "fmtlog: Failed to write to {}: {}",
+                        output,
+                        error
+                    );
+                }
+            }
+            ErrorPolicy::Fallback(_) => {
+                if let (Some(record), Some(fallback)) = (record, &self.streams.fallback) {
+                    // Errors of the fallback output are ignored.
+                    let _ = self.write(fallback, record, buf);
+                }
+            }
+            ErrorPolicy::Callback(f) => f.call(output, &error),
+        }
     }
 }
 
 impl Log for Logger {
     fn enabled(&self, metadata: &Metadata) -> bool {
-        self.level >= metadata.level()
+        self.filter().enabled(metadata)
     }
 
     fn log(&self, record: &Record) {
-        if !self.enabled(record.metadata()) {
-            return;
-        }
+        {
+            let filter = self.filter();
+
+            if !filter.enabled(record.metadata()) {
+                return;
+            }
+
+            let level = filter.record_level(record.target(), record.module_path());
 
-        if let Some(m) = record.module_path() {
-            if !self.modules.contains(&m) {
+            if level < record.level() || !filter.matches(record.args()) {
                 return;
             }
         }
 
-        // Get a writer or create new writer.
-        let mut writer = self
-            .writer
-            .get_or(|| {
-                RefCell::new(
-                    self.streams
-                        .iter()
-                        .map(|s| (s.0.to_stream().expect("Failed to open the file."), s.1))
-                        .collect(),
-                )
-            })
-            .borrow_mut();
-
-        // Write to all writers.
-        writer
-            .iter_mut()
-            .map(|w| self.format.write(&mut w.0, record, w.1))
-            .collect::<Result<Vec<_>, _>>()
-            .expect("Failed to write");
+        let res = BUFFER.try_with(|buf| match buf.try_borrow_mut() {
+            Ok(mut buf) => {
+                self.write_all(record, &mut buf);
+                buf.clear();
+            }
+            // Logged while rendering a record.
+            Err(_) => self.write_all(record, &mut Buffer::default()),
+        });
+
+        // Logged from a thread-local destructor.
+        if res.is_err() {
+            self.write_all(record, &mut Buffer::default());
+        }
     }
 
     fn flush(&self) {
-        use std::io::Write;
-
-        if let Some(writer) = self.writer.get() {
-            // Flush all writers.
-            writer
-                .borrow_mut()
-                .iter_mut()
-                .map(|w| w.0.flush())
-                .collect::<Result<Vec<_>, _>>()
-                .expect("Failed to flush the stream.");
+        // Flush all streams.
+        for stream in self.streams.outputs.iter() {
+            if let Err(e) = stream.flush() {
+                self.handle_error(stream.output(), e, None, &mut Buffer::default());
+            }
+        }
+
+        if let Some(f) = &self.streams.fallback {
+            let _ = f.flush();
         }
     }
 }
@@ -1,3 +1,138 @@
+use crate::{Config, FilterKey};
+use log::{LevelFilter, Metadata};
+
+/// The module filter.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    key: FilterKey,
+    level: LevelFilter,
+    modules: Modules,
+    excludes: Modules,
+    // (Module, Level)
+    directives: Vec<(Module, LevelFilter)>,
+    message: Option<Message>,
+}
+
+impl Filter {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            key: config.filter_key,
+            level: config.level,
+            modules: Modules::from(config.modules.clone()),
+            excludes: Modules::from(config.excludes.clone()),
+            directives: config
+                .directives
+                .iter()
+                .map(|(m, l)| (Module::from(m.clone()), *l))
+                .collect(),
+            message: config.message.clone().map(Message::new),
+        }
+    }
+
+    /// The most verbose level of all rules.
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|d| d.1)
+            .fold(self.level, std::cmp::max)
+    }
+
+    /// The level used when no module rule matches.
+    pub fn default_level(&self) -> LevelFilter {
+        self.level
+    }
+
+    pub fn set_level(&mut self, level: LevelFilter) {
+        self.level = level;
+    }
+
+    pub fn set_directive(&mut self, module: String, level: LevelFilter) {
+        let module = Module::from(module);
+
+        match self.directives.iter_mut().find(|d| d.0 == module) {
+            Some(d) => d.1 = level,
+            None => self.directives.push((module, level)),
+        }
+    }
+
+    pub fn remove_directive(&mut self, module: &str) {
+        let module = Module::from(module);
+        self.directives.retain(|d| d.0 != module);
+    }
+
+    /// Check whether the logs may be enabled.
+    pub fn enabled(&self, metadata: &Metadata) -> bool {
+        match self.key {
+            // The module path is unknown here.
+            FilterKey::ModulePath | FilterKey::Both => self.max_level() >= metadata.level(),
+            FilterKey::Target => self.level(&metadata.target()) >= metadata.level(),
+        }
+    }
+
+    /// Resolve the level of the record by the filter key.
+    pub fn record_level(&self, target: &str, module_path: Option<&str>) -> LevelFilter {
+        let module_path = module_path.unwrap_or(target);
+
+        match self.key {
+            FilterKey::ModulePath => self.level(&module_path),
+            FilterKey::Target => self.level(&target),
+            FilterKey::Both => std::cmp::min(self.level(&target), self.level(&module_path)),
+        }
+    }
+
+    /// Resolve the level of the module.
+    pub fn level<T: Into<Module> + Clone>(&self, module: &T) -> LevelFilter {
+        // Excluded modules take precedence over everything.
+        if self.excludes.matches(module) {
+            return LevelFilter::Off;
+        }
+
+        // The longest matching directive wins.
+        let directive = self
+            .directives
+            .iter()
+            .filter(|d| d.0.is_parent(module))
+            .max_by_key(|d| d.0.len());
+
+        match directive {
+            Some(d) => d.1,
+            None if self.modules.contains(module) => self.level,
+            None => LevelFilter::Off,
+        }
+    }
+
+    /// Check whether the message passes the filter.
+    pub fn matches(&self, message: &std::fmt::Arguments) -> bool {
+        match &self.message {
+            Some(m) => m.matches(&message.to_string()),
+            None => true,
+        }
+    }
+}
+
+/// The message filter.
+#[derive(Clone, Debug)]
+struct Message(
+    #[cfg(feature = "regex")] regex::Regex,
+    #[cfg(not(feature = "regex"))] String,
+);
+
+impl Message {
+    fn new(pattern: String) -> Self {
+        #[cfg(feature = "regex")]
+        return Self(regex::Regex::new(&pattern).expect("Invalid regex."));
+        #[cfg(not(feature = "regex"))]
+        return Self(pattern);
+    }
+
+    fn matches(&self, message: &str) -> bool {
+        #[cfg(feature = "regex")]
+        return self.0.is_match(message);
+        #[cfg(not(feature = "regex"))]
+        return message.contains(self.0.as_str());
+    }
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Modules(Vec<Module>);
 
@@ -43,6 +178,11 @@ impl Modules {
             return true;
         }
 
+        self.matches(module)
+    }
+
+    // Check whether any module is a parent of "module".
+    pub fn matches<T: Into<Module> + Clone>(&self, module: &T) -> bool {
         self.0.iter().any(|m| m.is_parent(module))
     }
 }
@@ -62,13 +202,17 @@ impl From<&str> for Module {
     }
 }
 
-impl Into<String> for Module {
-    fn into(self) -> String {
-        self.0
+impl From<Module> for String {
+    fn from(m: Module) -> Self {
+        m.0
     }
 }
 
 impl Module {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
     fn is_parent<T: Into<Module> + Clone>(&self, other: &T) -> bool {
         let parent = &self.0;
         let child = other.clone().into().0;
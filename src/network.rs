@@ -0,0 +1,335 @@
+use crate::Framing;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The number of records kept while disconnected.
+const CAPACITY: usize = 1024;
+// The timeout of connecting.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+// The longest time to wait for sending buffered records on flush.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+// The range of the delay before reconnecting.
+const MIN_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// The protocol of `Network`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    Tcp(Framing),
+    Udp,
+}
+
+/// A network stream which reconnects automatically.
+///
+/// Records are queued and sent by a background thread, so logging threads
+/// never wait for the network. While disconnected, records are buffered and
+/// the oldest ones are dropped when the buffer is full.
+#[derive(Debug)]
+pub struct Network {
+    addr: String,
+    protocol: Protocol,
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<State>,
+    // Notified when the state is changed.
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    buffer: VecDeque<Vec<u8>>,
+    // The number of records dropped since the last error.
+    dropped: usize,
+    // Whether the worker holds a record.
+    sending: bool,
+    // Whether the last connection failed.
+    down: bool,
+    closed: bool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Network {
+    /// Create a new instance and connect in the background.
+    pub fn new(addr: String, protocol: Protocol) -> io::Result<Self> {
+        let shared = Arc::new(Shared::default());
+
+        let worker = Worker {
+            addr: addr.clone(),
+            protocol,
+            addrs: Vec::new(),
+            socket: None,
+            backoff: MIN_BACKOFF,
+            shared: shared.clone(),
+        };
+        thread::Builder::new()
+            .name(format!("fmtlog-{}", addr))
+            .spawn(move || worker.run())?;
+
+        Ok(Self {
+            addr,
+            protocol,
+            shared,
+        })
+    }
+
+    /// Queue a record. (The framing is added.)
+    pub fn send(&self, record: &[u8]) -> io::Result<()> {
+        let frame = frame(self.protocol, record);
+
+        let dropped = {
+            let mut state = self.shared.lock();
+            // The record held by the worker is also counted.
+            if state.buffer.len() + state.sending as usize >= CAPACITY {
+                state.buffer.pop_front();
+                state.dropped += 1;
+            }
+            state.buffer.push_back(frame);
+            std::mem::take(&mut state.dropped)
+        };
+        self.shared.changed.notify_all();
+
+        match dropped {
+            0 => Ok(()),
+            n => Err(io::Error::other(format!(
+                "Disconnected from {}, {} record(s) dropped.",
+                self.addr, n
+            ))),
+        }
+    }
+
+    /// Wait until buffered records are sent, unless disconnected.
+    pub fn flush_buffer(&self) -> io::Result<()> {
+        self.flusher().flush()
+    }
+
+    /// Get a handle to wait for buffered records without borrowing this.
+    pub fn flusher(&self) -> Flusher {
+        Flusher {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+/// A handle to wait for buffered records of `Network`.
+#[derive(Debug)]
+pub struct Flusher {
+    shared: Arc<Shared>,
+}
+
+impl Flusher {
+    /// Wait until buffered records are sent, unless disconnected.
+    pub fn flush(&self) -> io::Result<()> {
+        let deadline = Instant::now() + FLUSH_TIMEOUT;
+        let mut state = self.shared.lock();
+
+        while (!state.buffer.is_empty() || state.sending) && !state.down {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+
+            state = self
+                .shared
+                .changed
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Network {
+    fn drop(&mut self) {
+        let _ = self.flush_buffer();
+        self.shared.lock().closed = true;
+        self.shared.changed.notify_all();
+    }
+}
+
+#[derive(Debug)]
+enum Socket {
+    Tcp(TcpStream),
+    Udp(UdpSocket),
+}
+
+/// The background thread sending records.
+struct Worker {
+    addr: String,
+    protocol: Protocol,
+    // Resolved addresses. (Resolved again when all of them fail.)
+    addrs: Vec<SocketAddr>,
+    socket: Option<Socket>,
+    backoff: Duration,
+    shared: Arc<Shared>,
+}
+
+impl Worker {
+    fn run(mut self) {
+        // The record being sent.
+        let mut pending: Option<Vec<u8>> = None;
+
+        loop {
+            let record = match pending.take() {
+                Some(r) => r,
+                None => match self.next() {
+                    Some(r) => r,
+                    None => return,
+                },
+            };
+
+            if self.socket.is_none() {
+                let res = self.connect();
+                self.set_down(res.is_err());
+
+                if res.is_err() {
+                    pending = Some(record);
+                    if !self.wait_backoff() {
+                        return;
+                    }
+                    continue;
+                }
+            }
+
+            let res = match self.socket.as_mut() {
+                Some(Socket::Tcp(s)) => s.write_all(&record),
+                Some(Socket::Udp(s)) => s.send(&record).map(|_| ()),
+                None => Ok(()),
+            };
+
+            if res.is_err() {
+                // Resend the whole record on a new connection.
+                // (The peer sees a partial record only when the connection breaks.)
+                self.socket = None;
+                pending = Some(record);
+            }
+        }
+    }
+
+    // Wait for the next record. Returns "None" when closed.
+    fn next(&self) -> Option<Vec<u8>> {
+        let mut state = self.shared.lock();
+        state.sending = false;
+        self.shared.changed.notify_all();
+
+        loop {
+            if let Some(record) = state.buffer.pop_front() {
+                state.sending = true;
+                return Some(record);
+            }
+
+            if state.closed {
+                return None;
+            }
+
+            state = self
+                .shared
+                .changed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    fn set_down(&self, down: bool) {
+        self.shared.lock().down = down;
+        self.shared.changed.notify_all();
+    }
+
+    // Wait before reconnecting. Returns "false" when closed.
+    fn wait_backoff(&mut self) -> bool {
+        let deadline = Instant::now() + self.backoff;
+        self.backoff = std::cmp::min(self.backoff * 2, MAX_BACKOFF);
+
+        let mut state = self.shared.lock();
+        while !state.closed {
+            let now = Instant::now();
+            if now >= deadline {
+                return true;
+            }
+
+            state = self
+                .shared
+                .changed
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+
+        false
+    }
+
+    fn connect(&mut self) -> io::Result<()> {
+        if self.addrs.is_empty() {
+            self.addrs = self.addr.to_socket_addrs()?.collect();
+        }
+
+        let mut last_err = None;
+
+        for addr in self.addrs.iter() {
+            let res = match self.protocol {
+                Protocol::Tcp(_) => {
+                    TcpStream::connect_timeout(addr, CONNECT_TIMEOUT).map(Socket::Tcp)
+                }
+                Protocol::Udp => {
+                    let local = match addr {
+                        SocketAddr::V4(_) => "0.0.0.0:0",
+                        SocketAddr::V6(_) => "[::]:0",
+                    };
+                    UdpSocket::bind(local).and_then(|s| {
+                        s.connect(addr)?;
+                        Ok(Socket::Udp(s))
+                    })
+                }
+            };
+
+            match res {
+                Ok(s) => {
+                    self.socket = Some(s);
+                    self.backoff = MIN_BACKOFF;
+                    return Ok(());
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        // The addresses may be changed.
+        self.addrs.clear();
+
+        Err(last_err.unwrap_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "No address is resolved.")
+        }))
+    }
+}
+
+fn frame(protocol: Protocol, record: &[u8]) -> Vec<u8> {
+    match protocol {
+        Protocol::Tcp(Framing::Newline) => {
+            let mut res = record.to_vec();
+            if !res.ends_with(b"\n") {
+                res.push(b'\n');
+            }
+            res
+        }
+        Protocol::Tcp(Framing::OctetCounting) => {
+            // RFC 6587: "<length> <message>" (without the trailing newline)
+            let record = record.strip_suffix(b"\n").unwrap_or(record);
+            let mut res = format!("{} ", record.len()).into_bytes();
+            res.extend_from_slice(record);
+            res
+        }
+        Protocol::Udp => record.to_vec(),
+    }
+}
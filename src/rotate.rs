@@ -0,0 +1,379 @@
+use crate::RotatingFile;
+use std::io::{self, Write};
+use std::time::SystemTime;
+use std::{fs, path, thread};
+
+#[cfg(feature = "chrono")]
+use crate::config::Period;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Duration, Local, NaiveDateTime, Timelike};
+
+/// A file which is rotated by its size or time.
+#[derive(Debug)]
+pub struct Rotator {
+    config: RotatingFile,
+    // The path with patterns evaluated.
+    path: path::PathBuf,
+    // "None" means not opened yet or failed.
+    file: Option<fs::File>,
+    size: u64,
+    // The start of the next period.
+    #[cfg(feature = "chrono")]
+    next: Option<NaiveDateTime>,
+    // The thread compressing and removing old files.
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl Rotator {
+    /// Create a new instance and open the file.
+    pub fn new(config: RotatingFile) -> io::Result<Self> {
+        let mut res = Self {
+            path: config.path.clone(),
+            config,
+            file: None,
+            size: 0,
+            #[cfg(feature = "chrono")]
+            next: None,
+            worker: None,
+        };
+
+        res.file()?;
+        Ok(res)
+    }
+
+    /// Write a log, rotating the file if needed.
+    pub fn write_record(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.file()?;
+
+        #[cfg(feature = "chrono")]
+        self.check_period()?;
+
+        if let Some(max) = self.config.max_size {
+            if self.size > 0 && self.size + buf.len() as u64 > max {
+                self.rotate()?;
+            }
+        }
+
+        match self.file()?.write_all(buf) {
+            Ok(()) => {
+                self.size += buf.len() as u64;
+                Ok(())
+            }
+            Err(e) => {
+                // Reopen the file next time.
+                self.file = None;
+                Err(e)
+            }
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(f) => f.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Close the file to open it again on the next write.
+    pub fn reopen(&mut self) {
+        self.file = None;
+    }
+
+    // Get the file, opening it if needed.
+    fn file(&mut self) -> io::Result<&mut fs::File> {
+        let file = match self.file.take() {
+            Some(f) => f,
+            None => {
+                self.path = self.resolve();
+                let f = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?;
+                let metadata = f.metadata()?;
+                self.size = metadata.len();
+
+                // An existing file may belong to an old period.
+                #[cfg(feature = "chrono")]
+                {
+                    let now = Local::now().naive_local();
+                    let modified = match metadata.modified() {
+                        Ok(t) if self.size > 0 => DateTime::<Local>::from(t).naive_local(),
+                        _ => now,
+                    };
+                    self.next = self
+                        .config
+                        .period
+                        .map(|p| p.next(std::cmp::min(modified, now)));
+                }
+
+                f
+            }
+        };
+
+        Ok(self.file.insert(file))
+    }
+
+    // Evaluate the patterns in the path.
+    fn resolve(&self) -> path::PathBuf {
+        #[cfg(feature = "chrono")]
+        if let (Some(_), Some(pattern)) = (self.config.period, self.config.path.to_str()) {
+            use std::fmt::Write;
+
+            let mut res = String::new();
+            // Invalid patterns are written as is.
+            if write!(res, "{}", Local::now().format(pattern)).is_ok() {
+                return res.into();
+            }
+        }
+
+        self.config.path.clone()
+    }
+
+    // Start a new file when a period has passed.
+    #[cfg(feature = "chrono")]
+    fn check_period(&mut self) -> io::Result<()> {
+        match self.next {
+            Some(next) if Local::now().naive_local() >= next => {}
+            _ => return Ok(()),
+        }
+
+        if self.resolve() == self.path {
+            self.rotate()?;
+        } else {
+            self.file = None;
+            let old = self.path.clone();
+            self.file()?;
+            self.clean_up(Some(old));
+        }
+
+        self.file().map(|_| ())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        // Don't rename files being compressed.
+        self.wait();
+        let path = &self.path;
+
+        match self.config.keep {
+            Some(0) => {
+                remove(path)?;
+                return Ok(());
+            }
+            Some(keep) => {
+                remove(&numbered(path, keep))?;
+                remove(&gz(&numbered(path, keep)))?;
+                shift(path, keep - 1)?;
+            }
+            None => {
+                // Find the first unused number.
+                let last = (1..)
+                    .find(|&n| !numbered(path, n).exists() && !gz(&numbered(path, n)).exists())
+                    .unwrap_or(1);
+                shift(path, last - 1)?;
+            }
+        }
+
+        let old = numbered(path, 1);
+        self.clean_up(Some(old));
+        Ok(())
+    }
+
+    // Compress the rotated file and remove old files in the background.
+    fn clean_up(&mut self, rotated: Option<path::PathBuf>) {
+        self.wait();
+
+        let config = self.config.clone();
+        let current = self.path.clone();
+
+        // Errors are ignored because the logs can't be reported.
+        self.worker = Some(thread::spawn(move || {
+            #[cfg(feature = "gzip")]
+            if let (true, Some(rotated)) = (config.compress, rotated) {
+                let _ = compress(&rotated);
+            }
+            #[cfg(not(feature = "gzip"))]
+            let _ = rotated;
+
+            let _ = prune(&config, &current);
+        }));
+    }
+
+    // Wait for the worker.
+    fn wait(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for Rotator {
+    fn drop(&mut self) {
+        self.wait();
+    }
+}
+
+// Remove rotated files exceeding the limits, from the oldest one.
+fn prune(config: &RotatingFile, current: &path::Path) -> io::Result<()> {
+    if config.keep.is_none() && config.max_total_size.is_none() && config.max_age.is_none() {
+        return Ok(());
+    }
+
+    let dir = match current.parent() {
+        Some(dir) if dir != path::Path::new("") => dir,
+        _ => path::Path::new("."),
+    };
+    let pattern = match config.path.file_name().and_then(|s| s.to_str()) {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+    #[cfg(feature = "chrono")]
+    let patterns = config.period.is_some();
+    #[cfg(not(feature = "chrono"))]
+    let patterns = false;
+
+    // (Modified, Size, Path)
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+
+        let name = match path.file_name().and_then(|s| s.to_str()) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        if path.file_name() == current.file_name()
+            || !metadata.is_file()
+            || !is_rotated(pattern, name, patterns)
+        {
+            continue;
+        }
+
+        files.push((metadata.modified()?, metadata.len(), path));
+    }
+
+    // The newest first.
+    files.sort_by_key(|f| std::cmp::Reverse(f.0));
+
+    let now = SystemTime::now();
+    let mut total = 0;
+    for (i, (modified, size, path)) in files.into_iter().enumerate() {
+        total += size;
+
+        let too_many = config.keep.is_some_and(|keep| i >= keep);
+        let too_large = config.max_total_size.is_some_and(|max| total > max);
+        let too_old = config
+            .max_age
+            .is_some_and(|max| now.duration_since(modified).is_ok_and(|age| age > max));
+
+        if too_many || too_large || too_old {
+            remove(&path)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Check whether "name" is "<pattern>", "<pattern>.<n>" or these with ".gz".
+fn is_rotated(pattern: &str, name: &str, patterns: bool) -> bool {
+    let name = name.strip_suffix(".gz").unwrap_or(name);
+    let unnumbered = name
+        .rsplit_once('.')
+        .filter(|(_, n)| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        .map(|(s, _)| s);
+
+    std::iter::once(name)
+        .chain(unnumbered)
+        .any(|name| matches(pattern, name, patterns))
+}
+
+// Match the name with the pattern.
+// With patterns, the name must be parsed back by the patterns. (e.g. "%Y" matches "2021")
+fn matches(pattern: &str, name: &str, patterns: bool) -> bool {
+    #[cfg(feature = "chrono")]
+    if patterns {
+        use chrono::format::{parse, Parsed, StrftimeItems};
+        return parse(&mut Parsed::new(), name, StrftimeItems::new(pattern)).is_ok();
+    }
+    #[cfg(not(feature = "chrono"))]
+    let _ = patterns;
+
+    pattern == name
+}
+
+// Compress "<path>" to "<path>.gz" and remove "<path>".
+#[cfg(feature = "gzip")]
+fn compress(path: &path::Path) -> io::Result<()> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let target = gz(path);
+    // Keep the half-written file away from pruning.
+    let mut temp = target.as_os_str().to_owned();
+    temp.push(".part");
+
+    let mut encoder = GzEncoder::new(fs::File::create(&temp)?, Compression::default());
+    io::copy(&mut fs::File::open(path)?, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+
+    fs::rename(&temp, &target)?;
+    fs::remove_file(path)
+}
+
+// "<path>.gz"
+fn gz(path: &path::Path) -> path::PathBuf {
+    let mut res = path.as_os_str().to_owned();
+    res.push(".gz");
+    res.into()
+}
+
+// "<path>.<n>"
+fn numbered(path: &path::Path, n: usize) -> path::PathBuf {
+    let mut res = path.as_os_str().to_owned();
+    res.push(format!(".{}", n));
+    res.into()
+}
+
+// Rename "<path>.<n>" to "<path>.<n+1>" for n = last..1, and "<path>" to "<path>.1".
+// (Compressed files are also renamed.)
+fn shift(path: &path::Path, last: usize) -> io::Result<()> {
+    for n in (1..=last).rev() {
+        rename(&numbered(path, n), &numbered(path, n + 1))?;
+        rename(&gz(&numbered(path, n)), &gz(&numbered(path, n + 1)))?;
+    }
+    rename(path, &numbered(path, 1))
+}
+
+// Missing files are ignored.
+fn rename(from: &path::Path, to: &path::Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn remove(path: &path::Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Period {
+    // The start of the period after "time".
+    fn next(self, time: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Period::Hourly => {
+                let start = time.date().and_hms_opt(time.hour(), 0, 0).unwrap_or(time);
+                start + Duration::hours(1)
+            }
+            Period::Daily => {
+                let start = time.date().and_hms_opt(0, 0, 0).unwrap_or(time);
+                start + Duration::days(1)
+            }
+        }
+    }
+}
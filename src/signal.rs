@@ -0,0 +1,143 @@
+use std::fs;
+use std::io::{self, Read};
+use std::os::unix::io::FromRawFd;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::thread;
+
+// The write end of the pipe notifying signals. (-1 means not installed.)
+static PIPE: AtomicI32 = AtomicI32::new(-1);
+
+extern "C" fn handler(_: libc::c_int) {
+    let fd = PIPE.load(Ordering::Relaxed);
+    if fd >= 0 {
+        // Only async-signal-safe functions can be called here.
+        // ("write" may change "errno" of the interrupted code, so restore it.)
+        unsafe {
+            let errno = *errno_location();
+            libc::write(fd, b"\0".as_ptr() as *const libc::c_void, 1);
+            *errno_location() = errno;
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "redox"))]
+unsafe fn errno_location() -> *mut libc::c_int {
+    libc::__errno_location()
+}
+
+#[cfg(any(target_os = "android", target_os = "netbsd", target_os = "openbsd"))]
+unsafe fn errno_location() -> *mut libc::c_int {
+    libc::__errno()
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "emscripten",
+    target_os = "redox",
+    target_os = "android",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+unsafe fn errno_location() -> *mut libc::c_int {
+    libc::__error()
+}
+
+/// Call `f` in a background thread each time `SIGHUP` is received.
+pub fn on_sighup<F: Fn() + Send + 'static>(f: F) -> io::Result<()> {
+    let fds = pipe()?;
+    let mut reader = unsafe { fs::File::from_raw_fd(fds[0]) };
+    let writer = unsafe { fs::File::from_raw_fd(fds[1]) };
+
+    // The handler must not block when the pipe is full.
+    // (One pending byte is enough to reopen files.)
+    set_flag(fds[1], libc::F_GETFL, libc::F_SETFL, libc::O_NONBLOCK)?;
+
+    if PIPE
+        .compare_exchange(-1, fds[1], Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "The SIGHUP handler is already installed.",
+        ));
+    }
+
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = handler as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        action.sa_flags = libc::SA_RESTART;
+        libc::sigemptyset(&mut action.sa_mask);
+
+        if libc::sigaction(libc::SIGHUP, &action, std::ptr::null_mut()) != 0 {
+            PIPE.store(-1, Ordering::SeqCst);
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    // The write end is used by the handler forever.
+    std::mem::forget(writer);
+
+    thread::Builder::new()
+        .name(String::from("fmtlog-sighup"))
+        .spawn(move || {
+            let mut buf = [0; 1];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(_) => f(),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(_) => break,
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+// Create a pipe which is not leaked to child processes.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn pipe() -> io::Result<[libc::c_int; 2]> {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(fds)
+}
+
+// Create a pipe which is not leaked to child processes.
+// (Other threads may fork before the flag is set.)
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn pipe() -> io::Result<[libc::c_int; 2]> {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    for fd in fds.iter() {
+        if let Err(e) = set_flag(*fd, libc::F_GETFD, libc::F_SETFD, libc::FD_CLOEXEC) {
+            unsafe {
+                libc::close(fds[0]);
+                libc::close(fds[1]);
+            }
+            return Err(e);
+        }
+    }
+
+    Ok(fds)
+}
+
+// Add a flag to the file descriptor with "fcntl".
+fn set_flag(
+    fd: libc::c_int,
+    get: libc::c_int,
+    set: libc::c_int,
+    flag: libc::c_int,
+) -> io::Result<()> {
+    let flags = unsafe { libc::fcntl(fd, get) };
+    if flags < 0 || unsafe { libc::fcntl(fd, set, flags | flag) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
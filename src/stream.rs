@@ -1,11 +1,24 @@
+use crate::network::Network;
+use crate::rotate::Rotator;
+use crate::Output;
+use std::sync::{Mutex, MutexGuard, PoisonError};
 use std::{fs, io};
 
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+
 /// Stream
 #[derive(Debug)]
 pub enum Stream {
     Stdout(io::Stdout),
     Stderr(io::Stderr),
     File(fs::File),
+    Network(Box<Network>),
+    Rotating(Box<Rotator>),
+    #[cfg(unix)]
+    UnixDatagram(UnixDatagram),
+    #[cfg(unix)]
+    Journald(UnixDatagram),
 }
 
 impl From<io::Stdout> for Stream {
@@ -26,12 +39,42 @@ impl From<fs::File> for Stream {
     }
 }
 
+#[cfg(unix)]
+impl From<UnixDatagram> for Stream {
+    fn from(s: UnixDatagram) -> Self {
+        Stream::UnixDatagram(s)
+    }
+}
+
+impl From<Network> for Stream {
+    fn from(s: Network) -> Self {
+        Stream::Network(Box::new(s))
+    }
+}
+
+impl From<Rotator> for Stream {
+    fn from(s: Rotator) -> Self {
+        Stream::Rotating(Box::new(s))
+    }
+}
+
+impl Stream {
+    /// Whether the stream reopens itself after errors.
+    pub fn recovers(&self) -> bool {
+        matches!(self, Stream::Network(_) | Stream::Rotating(_))
+    }
+}
+
 impl io::Write for Stream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         match self {
             Stream::Stdout(w) => w.write(buf),
             Stream::Stderr(w) => w.write(buf),
             Stream::File(w) => w.write(buf),
+            Stream::Network(w) => w.send(buf).map(|_| buf.len()),
+            Stream::Rotating(w) => w.write_record(buf).map(|_| buf.len()),
+            #[cfg(unix)]
+            Stream::UnixDatagram(w) | Stream::Journald(w) => w.send(buf),
         }
     }
 
@@ -40,6 +83,98 @@ impl io::Write for Stream {
             Stream::Stdout(w) => w.flush(),
             Stream::Stderr(w) => w.flush(),
             Stream::File(w) => w.flush(),
+            Stream::Network(w) => w.flush_buffer(),
+            Stream::Rotating(w) => w.flush(),
+            #[cfg(unix)]
+            Stream::UnixDatagram(_) | Stream::Journald(_) => Ok(()),
+        }
+    }
+}
+
+/// A stream opened once and shared by all threads.
+#[derive(Debug)]
+pub struct SharedStream {
+    output: Output,
+    colorize: bool,
+    // "None" means not opened yet or failed.
+    stream: Mutex<Option<Stream>>,
+}
+
+impl SharedStream {
+    /// Create a new instance without opening the stream.
+    pub fn new(output: Output, colorize: bool) -> Self {
+        Self {
+            output,
+            colorize,
+            stream: Mutex::new(None),
+        }
+    }
+
+    /// Create a new instance and open the stream.
+    pub fn open(output: Output, colorize: bool) -> io::Result<Self> {
+        let stream = output.to_stream()?;
+        Ok(Self {
+            output,
+            colorize,
+            stream: Mutex::new(Some(stream)),
+        })
+    }
+
+    pub fn output(&self) -> &Output {
+        &self.output
+    }
+
+    pub fn colorize(&self) -> bool {
+        self.colorize
+    }
+
+    /// Whether the stream formats records by itself.
+    pub fn is_structured(&self) -> bool {
+        #[cfg(unix)]
+        return matches!(self.output, Output::Journald | Output::JournaldSocket(_));
+        #[cfg(not(unix))]
+        return false;
+    }
+
+    /// Lock the stream. (Each record must be written while locking.)
+    pub fn lock(&self) -> MutexGuard<'_, Option<Stream>> {
+        self.stream.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Flush the stream.
+    /// (Network streams are waited for without locking, so logging is not blocked.)
+    pub fn flush(&self) -> io::Result<()> {
+        let flusher = match self.lock().as_mut() {
+            Some(Stream::Network(n)) => n.flusher(),
+            Some(s) => return io::Write::flush(s),
+            None => return Ok(()),
+        };
+        flusher.flush()
+    }
+
+    /// Close the file to open it again on the next write.
+    pub fn reopen(&self) {
+        let mut stream = self.lock();
+        match &mut *stream {
+            Some(Stream::File(_)) => *stream = None,
+            Some(Stream::Rotating(r)) => r.reopen(),
+            _ => {}
+        }
+    }
+}
+
+/// All streams of a logger.
+#[derive(Debug)]
+pub struct Streams {
+    pub outputs: Vec<SharedStream>,
+    pub fallback: Option<SharedStream>,
+}
+
+impl Streams {
+    /// Reopen all files.
+    pub fn reopen(&self) {
+        for stream in self.outputs.iter().chain(&self.fallback) {
+            stream.reopen();
         }
     }
 }